@@ -1,25 +1,38 @@
 use super::*;
-use std::collections::BTreeMap;
+use indexmap::IndexMap;
 
 #[derive(Debug)]
 pub struct Account {
     pub comment: Option<String>,
     pub nonce: U64Value,
     pub balance: BigUintValue,
-    pub storage: BTreeMap<BytesKey, BytesValue>,
+    pub storage: IndexMap<BytesKey, BytesValue>,
     pub code: Option<BytesValue>,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<AccountRaw> for Account {
-    fn interpret_from(from: AccountRaw, context: &InterpreterContext) -> Self {
-        Account {
+    fn interpret_from(from: AccountRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(Account {
             comment: from.comment,
-            nonce: U64Value::interpret_from(from.nonce, context),
-            balance: BigUintValue::interpret_from(from.balance, context),
-            storage: from.storage.into_iter().map(|(k, v)| (
-                BytesKey::interpret_from(k, context), 
-                BytesValue::interpret_from(v, context))).collect(),
-            code: from.code.map(|c| BytesValue::interpret_from(c, context)),
+            nonce: U64Value::interpret_from(from.nonce, context)?,
+            balance: BigUintValue::interpret_from(from.balance, context)?,
+            storage: interpret_map(from.storage, context)?,
+            code: from.code.map(|c| BytesValue::interpret_from(c, context)).transpose()?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<AccountRaw> for Account {
+    fn into_raw(self) -> AccountRaw {
+        AccountRaw {
+            comment: self.comment,
+            nonce: self.nonce.into_raw(),
+            balance: self.balance.into_raw(),
+            storage: into_raw_map(self.storage),
+            code: self.code.map(IntoRaw::into_raw),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -27,18 +40,23 @@ impl InterpretableFrom<AccountRaw> for Account {
 #[derive(Debug)]
 pub enum CheckStorage {
     Star,
-    Equal(BTreeMap<BytesKey, CheckValue<BytesValue>>),
+    Equal(IndexMap<BytesKey, CheckValue<BytesValue>>),
 }
 
 impl InterpretableFrom<CheckStorageRaw> for CheckStorage {
-    fn interpret_from(from: CheckStorageRaw, context: &InterpreterContext) -> Self {
-        match from {
+    fn interpret_from(from: CheckStorageRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(match from {
             CheckStorageRaw::Star => CheckStorage::Star,
-            CheckStorageRaw::Equal(m) => CheckStorage::Equal(
-                m.into_iter().map(|(k, v)| (
-                    BytesKey::interpret_from(k, context), 
-                    CheckValue::<BytesValue>::interpret_from(v, context))).collect(),
-            )
+            CheckStorageRaw::Equal(m) => CheckStorage::Equal(interpret_map(m, context)?),
+        })
+    }
+}
+
+impl IntoRaw<CheckStorageRaw> for CheckStorage {
+    fn into_raw(self) -> CheckStorageRaw {
+        match self {
+            CheckStorage::Star => CheckStorageRaw::Star,
+            CheckStorage::Equal(m) => CheckStorageRaw::Equal(into_raw_map(m)),
         }
     }
 }
@@ -57,17 +75,33 @@ pub struct CheckAccount {
     pub storage: CheckStorage,
     pub code: Option<CheckValue<BytesValue>>,
     pub async_call_data: CheckValue<BytesValue>,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<CheckAccountRaw> for CheckAccount {
-    fn interpret_from(from: CheckAccountRaw, context: &InterpreterContext) -> Self {
-        CheckAccount {
+    fn interpret_from(from: CheckAccountRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(CheckAccount {
             comment: from.comment,
-            nonce: CheckValue::<U64Value>::interpret_from(from.nonce, context),
-            balance: CheckValue::<BigUintValue>::interpret_from(from.balance, context),
-            storage: CheckStorage::interpret_from(from.storage, context),
-            code: from.code.map(|c| CheckValue::<BytesValue>::interpret_from(c, context)),
-            async_call_data: CheckValue::<BytesValue>::interpret_from(from.async_call_data, context),
+            nonce: CheckValue::<U64Value>::interpret_from(from.nonce, context)?,
+            balance: CheckValue::<BigUintValue>::interpret_from(from.balance, context)?,
+            storage: CheckStorage::interpret_from(from.storage, context)?,
+            code: from.code.map(|c| CheckValue::<BytesValue>::interpret_from(c, context)).transpose()?,
+            async_call_data: CheckValue::<BytesValue>::interpret_from(from.async_call_data, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<CheckAccountRaw> for CheckAccount {
+    fn into_raw(self) -> CheckAccountRaw {
+        CheckAccountRaw {
+            comment: self.comment,
+            nonce: self.nonce.into_raw(),
+            balance: self.balance.into_raw(),
+            storage: self.storage.into_raw(),
+            code: self.code.map(IntoRaw::into_raw),
+            async_call_data: self.async_call_data.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -75,16 +109,23 @@ impl InterpretableFrom<CheckAccountRaw> for CheckAccount {
 #[derive(Debug)]
 pub struct CheckAccounts {
     pub other_accounts_allowed: bool,
-    pub accounts: BTreeMap<AddressKey, CheckAccount>
+    pub accounts: IndexMap<AddressKey, CheckAccount>
 }
 
 impl InterpretableFrom<CheckAccountsRaw> for CheckAccounts {
-    fn interpret_from(from: CheckAccountsRaw, context: &InterpreterContext) -> Self {
-        CheckAccounts {
+    fn interpret_from(from: CheckAccountsRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(CheckAccounts {
             other_accounts_allowed: from.other_accounts_allowed,
-            accounts: from.accounts.into_iter().map(|(k, v)| (
-                AddressKey::interpret_from(k, context), 
-                CheckAccount::interpret_from(v, context))).collect(),
+            accounts: interpret_map(from.accounts, context)?,
+        })
+    }
+}
+
+impl IntoRaw<CheckAccountsRaw> for CheckAccounts {
+    fn into_raw(self) -> CheckAccountsRaw {
+        CheckAccountsRaw {
+            other_accounts_allowed: self.other_accounts_allowed,
+            accounts: into_raw_map(self.accounts),
         }
     }
 }
@@ -1,6 +1,6 @@
 use super::*;
 use std::fmt;
-use std::collections::BTreeMap;
+use indexmap::IndexMap;
 use serde::{Serialize, Deserialize};
 use serde::ser::{Serializer, SerializeMap};
 use serde::de::{self, Deserializer, Visitor, MapAccess};
@@ -14,16 +14,19 @@ pub struct AccountRaw {
 
     pub nonce: ValueSubTree,
     pub balance: ValueSubTree,
-    pub storage: BTreeMap<String, ValueSubTree>,
+    pub storage: IndexMap<String, ValueSubTree>,
     
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<ValueSubTree>,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 pub enum CheckStorageRaw {
     Star,
-    Equal(BTreeMap<String, ValueSubTree>)
+    Equal(IndexMap<String, ValueSubTree>)
 }
 
 impl CheckStorageRaw {
@@ -65,7 +68,7 @@ impl<'de> Visitor<'de> for CheckStorageRawVisitor {
         E: de::Error,
     {
         if value == "*" {
-            Ok(CheckStorageRaw::Star)    
+            Ok(CheckStorageRaw::Star)
         } else {
             Err(de::Error::custom("only '*' allowed as logs string value"))
         }
@@ -75,7 +78,7 @@ impl<'de> Visitor<'de> for CheckStorageRawVisitor {
     where
         M: MapAccess<'de>,
     {
-        let mut map = BTreeMap::<String, ValueSubTree>::new();
+        let mut map = IndexMap::<String, ValueSubTree>::new();
 
         // While there are entries remaining in the input, add them
         // into our map.
@@ -121,10 +124,13 @@ pub struct CheckAccountRaw {
     #[serde(default)]
     #[serde(skip_serializing_if = "ValueSubTree::is_empty_string")]
     pub async_call_data: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 pub enum CheckAccountRawOrNothing {
-    Some(CheckAccountRaw),
+    Some(Box<CheckAccountRaw>),
     Nothing
 }
 
@@ -164,7 +170,7 @@ impl<'de> Deserialize<'de> for CheckAccountRawOrNothing {
 
 pub struct CheckAccountsRaw {
     pub other_accounts_allowed: bool,
-    pub accounts: BTreeMap<String, CheckAccountRaw>
+    pub accounts: IndexMap<String, CheckAccountRaw>
 }
 
 impl Serialize for CheckAccountsRaw {
@@ -197,7 +203,7 @@ impl<'de> Visitor<'de> for CheckAccountRawsVisitor {
     where
         M: MapAccess<'de>,
     {
-        let mut accounts = BTreeMap::<String, CheckAccountRaw>::new();
+        let mut accounts = IndexMap::<String, CheckAccountRaw>::new();
         let mut other_accounts_allowed = false;
 
         // While there are entries remaining in the input, add them
@@ -206,7 +212,7 @@ impl<'de> Visitor<'de> for CheckAccountRawsVisitor {
             if key == "+" {
                 other_accounts_allowed = true;
             } else if let CheckAccountRawOrNothing::Some(check_account) = value {
-                accounts.insert(key, check_account);
+                accounts.insert(key, *check_account);
             } else {
                 return Err(de::Error::custom("invalid CheckAccountRaw"))
             }
@@ -1,7 +1,48 @@
 use super::*;
 use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use std::fmt;
 
+const BECH32_BARE_PREFIX: &str = "erd1";
+
+/// In address positions, bare `erd1...` bech32 addresses are also accepted.
+fn interpret_address_string(s: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+    if s.starts_with(BECH32_BARE_PREFIX) {
+        Ok(bech32_decode(s)?.to_vec())
+    } else {
+        interpret_string(s, context)
+    }
+}
+
+/// Addresses must be exactly 32 bytes long.
+/// In lenient mode shorter ones are padded with zeros and longer ones truncated, with a warning,
+/// so that two malformed map keys only collide if they agree on their first 32 bytes, up to trailing zeros.
+fn address_from_bytes(
+    bytes: Vec<u8>,
+    original: String,
+    location: Option<&SourceLocation>,
+    context: &InterpreterContext,
+) -> Result<[u8; 32], MandosError> {
+    if bytes.len() != 32 {
+        context.report(MandosError::InvalidAddressLength {
+            value: original,
+            length: bytes.len(),
+        }.at(location))?;
+    }
+    let mut value = [0u8; 32];
+    let length = bytes.len().min(32);
+    value[.. length].copy_from_slice(&bytes[.. length]);
+    Ok(value)
+}
+
+fn interpret_address_subtree(vst: &ValueSubTree, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+    match vst {
+        ValueSubTree::Str(s, location) => interpret_address_string(s, context)
+            .map_err(|error| error.at(location.as_ref())),
+        _ => interpret_subtree(vst, context),
+    }
+}
+
 #[derive(Debug, Eq)]
 pub struct AddressKey {
     pub value: [u8; 32],
@@ -10,7 +51,7 @@ pub struct AddressKey {
 
 impl Ord for AddressKey {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.original.cmp(&other.original)
+        self.value.cmp(&other.value)
     }
 }
 
@@ -22,20 +63,49 @@ impl PartialOrd for AddressKey {
 
 impl PartialEq for AddressKey {
     fn eq(&self, other: &Self) -> bool {
-        self.original == other.original
+        self.value == other.value
     }
 }
 
-impl InterpretableFrom<String> for AddressKey {
-    fn interpret_from(from: String, context: &InterpreterContext) -> Self {
-        let bytes = interpret_string(from.as_str(), context);
-        let mut value = [0u8; 32];
-        if bytes.len() == 32 {
-            value.copy_from_slice(&bytes[..]);
-        }
+impl Hash for AddressKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl AddressKey {
+    pub fn to_bech32(&self) -> String {
+        bech32_encode(&self.value)
+    }
+}
+
+/// The original is written as `address:`/`sc:` where possible, hex otherwise.
+impl From<[u8; 32]> for AddressKey {
+    fn from(value: [u8; 32]) -> Self {
         AddressKey {
+            value,
+            original: format_value(&value, FormatHint::Address),
+        }
+    }
+}
+
+impl InterpretableFrom<String> for AddressKey {
+    fn interpret_from(from: String, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_address_string(from.as_str(), context)?;
+        let value = address_from_bytes(bytes, from.clone(), None, context)?;
+        Ok(AddressKey {
             value,
             original: from,
+        })
+    }
+}
+
+impl IntoRaw<String> for AddressKey {
+    fn into_raw(self) -> String {
+        if self.original.is_empty() {
+            format_value(&self.value, FormatHint::Address)
+        } else {
+            self.original
         }
     }
 }
@@ -53,16 +123,48 @@ pub struct AddressValue {
 }
 
 
-impl InterpretableFrom<ValueSubTree> for AddressValue {
-    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Self {
-        let bytes = interpret_subtree(&from, context);
-        let mut value = [0u8; 32];
-        if bytes.len() == 32 {
-            value.copy_from_slice(&bytes[..]);
+impl AddressValue {
+    pub fn to_bech32(&self) -> String {
+        bech32_encode(&self.value)
+    }
+}
+
+/// The original is written as `address:`/`sc:` where possible, hex otherwise.
+impl From<[u8; 32]> for AddressValue {
+    fn from(value: [u8; 32]) -> Self {
+        AddressValue {
+            value,
+            original: ValueSubTree::from(format_value(&value, FormatHint::Address)),
         }
+    }
+}
+
+impl From<&AddressKey> for AddressValue {
+    fn from(key: &AddressKey) -> Self {
         AddressValue {
+            value: key.value,
+            original: ValueSubTree::from(key.original.as_str()),
+        }
+    }
+}
+
+impl InterpretableFrom<ValueSubTree> for AddressValue {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_address_subtree(&from, context)?;
+        let value = address_from_bytes(bytes, from.to_string(), from.location(), context)?;
+        Ok(AddressValue {
             value,
             original: from,
+        })
+    }
+}
+
+impl IntoRaw<ValueSubTree> for AddressValue {
+    fn into_raw(self) -> ValueSubTree {
+        if self.original.is_empty_string() {
+            ValueSubTree::from(format_value(&self.value, FormatHint::Address))
+        } else {
+            self.original
         }
     }
 }
@@ -72,3 +174,10 @@ impl fmt::Display for AddressValue {
         self.original.fmt(f)
     }
 }
+
+impl Locatable for AddressValue {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
@@ -0,0 +1,33 @@
+use super::error::*;
+use bech32::{FromBase32, ToBase32, Variant};
+
+/// Human-readable part of Elrond bech32 addresses.
+pub const BECH32_HRP: &str = "erd";
+
+/// Decodes an `erd1...` address to its 32 bytes.
+pub fn bech32_decode(s: &str) -> Result<[u8; 32], MandosError> {
+    let invalid = |reason: String| MandosError::InvalidBech32 {
+        value: s.to_string(),
+        reason,
+    };
+    let (hrp, data, variant) = bech32::decode(s).map_err(|err| invalid(err.to_string()))?;
+    if hrp != BECH32_HRP {
+        return Err(invalid(format!("expected prefix `{}`, found `{}`", BECH32_HRP, hrp)));
+    }
+    if variant != Variant::Bech32 {
+        return Err(invalid("bech32m is not supported".to_string()));
+    }
+    let bytes = Vec::<u8>::from_base32(&data).map_err(|err| invalid(err.to_string()))?;
+    if bytes.len() != 32 {
+        return Err(invalid(format!("expected 32 bytes, found {}", bytes.len())));
+    }
+    let mut address = [0u8; 32];
+    address.copy_from_slice(&bytes[..]);
+    Ok(address)
+}
+
+/// Formats 32 address bytes as `erd1...`.
+pub fn bech32_encode(address: &[u8; 32]) -> String {
+    bech32::encode(BECH32_HRP, address.to_base32(), Variant::Bech32)
+        .expect("the erd prefix is always valid")
+}
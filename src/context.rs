@@ -1,8 +1,198 @@
+use super::error::*;
+use super::file_loader::*;
+use super::unknown_fields::*;
+use super::value_prefix::*;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 
-pub struct InterpreterContext{}
+/// How to treat problems after which interpretation could still produce a value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InterpreterMode {
+    /// Fail with an error.
+    #[default]
+    Strict,
+
+    /// Carry on, recording a warning in the context.
+    Lenient,
+}
+
+/// VM type of WebAssembly contracts, as found in smart contract addresses.
+pub const VM_TYPE_WASM: [u8; 2] = [5, 0];
+
+/// Everything value interpretation depends on.
+pub struct InterpreterContext {
+    context_path: PathBuf,
+    file_loader: Box<dyn FileLoader>,
+    lazy_file_loading: bool,
+    mode: InterpreterMode,
+    unknown_field_policy: UnknownFieldPolicy,
+    vm_type: [u8; 2],
+    prefix_handlers: Vec<(String, Box<dyn ValuePrefixHandler>)>,
+    warnings: RefCell<Vec<MandosError>>,
+}
 
 impl Default for InterpreterContext {
     fn default() -> Self {
-        InterpreterContext{}
+        InterpreterContext::builder().build()
+    }
+}
+
+impl InterpreterContext {
+    pub fn builder() -> InterpreterContextBuilder {
+        InterpreterContextBuilder::default()
+    }
+
+    pub fn new<P: AsRef<Path>>(context_path: P) -> Self {
+        InterpreterContext::builder()
+            .context_path(context_path)
+            .build()
+    }
+
+    /// Context for a scenario file, with `file:` paths resolved relative to its parent directory.
+    pub fn for_scenario_file<P: AsRef<Path>>(scenario_path: P) -> Self {
+        InterpreterContext::builder()
+            .scenario_file(scenario_path)
+            .build()
+    }
+
+    /// Directory of the scenario being interpreted. `file:` paths are relative to it.
+    pub fn context_path(&self) -> &Path {
+        self.context_path.as_path()
+    }
+
+    pub fn file_loader(&self) -> &dyn FileLoader {
+        self.file_loader.as_ref()
+    }
+
+    /// If set, `BytesValue`s written as `file:...` are not read during interpretation.
+    /// Their resolved path is kept in `lazy_file` instead, for `BytesValue::load` to read later.
+    pub fn lazy_file_loading(&self) -> bool {
+        self.lazy_file_loading
+    }
+
+    pub fn mode(&self) -> InterpreterMode {
+        self.mode
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.mode == InterpreterMode::Strict
+    }
+
+    /// What parsing a scenario file for this context does with fields that are not part of the Mandos format.
+    pub fn unknown_field_policy(&self) -> UnknownFieldPolicy {
+        self.unknown_field_policy
+    }
+
+    /// VM type used in `sc:` addresses.
+    pub fn vm_type(&self) -> &[u8] {
+        &self.vm_type[..]
+    }
+
+    pub fn prefix_handler(&self, s: &str) -> Option<(&str, &dyn ValuePrefixHandler)> {
+        self.prefix_handlers
+            .iter()
+            .find(|(prefix, _)| s.starts_with(prefix.as_str()))
+            .map(|(prefix, handler)| (prefix.as_str(), handler.as_ref()))
+    }
+
+    /// In strict mode returns the error, in lenient mode records it as a warning.
+    pub fn report(&self, error: MandosError) -> Result<(), MandosError> {
+        match self.mode {
+            InterpreterMode::Strict => Err(error),
+            InterpreterMode::Lenient => {
+                self.warnings.borrow_mut().push(error);
+                Ok(())
+            },
+        }
+    }
+
+    /// Problems ignored so far in lenient mode.
+    pub fn warnings(&self) -> Vec<MandosError> {
+        self.warnings.borrow().clone()
+    }
+
+    pub fn take_warnings(&self) -> Vec<MandosError> {
+        self.warnings.borrow_mut().split_off(0)
+    }
+}
+
+pub struct InterpreterContextBuilder {
+    context_path: PathBuf,
+    file_loader: Option<Box<dyn FileLoader>>,
+    lazy_file_loading: bool,
+    mode: InterpreterMode,
+    unknown_field_policy: UnknownFieldPolicy,
+    vm_type: [u8; 2],
+    prefix_handlers: Vec<(String, Box<dyn ValuePrefixHandler>)>,
+}
+
+impl Default for InterpreterContextBuilder {
+    fn default() -> Self {
+        InterpreterContextBuilder {
+            context_path: PathBuf::new(),
+            file_loader: None,
+            lazy_file_loading: false,
+            mode: InterpreterMode::default(),
+            unknown_field_policy: UnknownFieldPolicy::default(),
+            vm_type: VM_TYPE_WASM,
+            prefix_handlers: Vec::new(),
+        }
+    }
+}
+
+impl InterpreterContextBuilder {
+    pub fn context_path<P: AsRef<Path>>(mut self, context_path: P) -> Self {
+        self.context_path = context_path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets the context path to the directory containing the scenario file.
+    pub fn scenario_file<P: AsRef<Path>>(self, scenario_path: P) -> Self {
+        let context_path = scenario_path.as_ref().parent().unwrap_or_else(|| Path::new(""));
+        self.context_path(context_path)
+    }
+
+    pub fn file_loader<L: FileLoader + 'static>(mut self, file_loader: L) -> Self {
+        self.file_loader = Some(Box::new(file_loader));
+        self
+    }
+
+    pub fn lazy_file_loading(mut self, lazy_file_loading: bool) -> Self {
+        self.lazy_file_loading = lazy_file_loading;
+        self
+    }
+
+    pub fn mode(mut self, mode: InterpreterMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn unknown_field_policy(mut self, unknown_field_policy: UnknownFieldPolicy) -> Self {
+        self.unknown_field_policy = unknown_field_policy;
+        self
+    }
+
+    pub fn vm_type(mut self, vm_type: [u8; 2]) -> Self {
+        self.vm_type = vm_type;
+        self
+    }
+
+    /// Values starting with `prefix` (including the colon, e.g. `fp18:`) will be interpreted by `handler`.
+    pub fn prefix_handler<H: ValuePrefixHandler + 'static>(mut self, prefix: &str, handler: H) -> Self {
+        self.prefix_handlers.push((prefix.to_string(), Box::new(handler)));
+        self
+    }
+
+    pub fn build(self) -> InterpreterContext {
+        InterpreterContext {
+            context_path: self.context_path,
+            file_loader: self.file_loader.unwrap_or_else(|| Box::new(DiskFileLoader)),
+            lazy_file_loading: self.lazy_file_loading,
+            mode: self.mode,
+            unknown_field_policy: self.unknown_field_policy,
+            vm_type: self.vm_type,
+            prefix_handlers: self.prefix_handlers,
+            warnings: RefCell::new(Vec::new()),
+        }
     }
 }
@@ -0,0 +1,50 @@
+use super::error::*;
+use super::scenario::*;
+use super::world_state::*;
+
+/// A log entry produced by a contract call.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxLog {
+    pub address: [u8; 32],
+    pub identifier: Vec<u8>,
+    pub topics: Vec<Vec<u8>>,
+    pub data: Vec<u8>,
+}
+
+/// Outcome of a contract call or deploy, as reported by a `ContractExecutor`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxResult {
+    pub out: Vec<Vec<u8>>,
+    pub status: u64,
+    pub message: Vec<u8>,
+    pub logs: Vec<TxLog>,
+    pub gas: u64,
+    pub refund: u64,
+}
+
+/// Backend that runs contract code, e.g. a wasm VM, a native build of the contract, or a mock.
+///
+/// The executor does all the bookkeeping of a transaction on the world state:
+/// value transfer, sender nonce, storage changes, and creating the deployed account,
+/// typically at the address predicted by `WorldState::new_address`.
+/// Failed transactions are reported through the result status;
+/// errors are for when the executor itself cannot run the transaction.
+pub trait ContractExecutor {
+    fn sc_call(&mut self, tx: &TxCall, world: &mut WorldState) -> Result<TxResult, ExecutionError>;
+
+    fn sc_deploy(&mut self, tx: &TxDeploy, world: &mut WorldState) -> Result<TxResult, ExecutionError>;
+}
+
+/// For scenarios without contract calls: every call or deploy is reported as unsupported.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoContractExecutor;
+
+impl ContractExecutor for NoContractExecutor {
+    fn sc_call(&mut self, _tx: &TxCall, _world: &mut WorldState) -> Result<TxResult, ExecutionError> {
+        Err(ExecutionError::UnsupportedStep { step: "scCall" })
+    }
+
+    fn sc_deploy(&mut self, _tx: &TxDeploy, _world: &mut WorldState) -> Result<TxResult, ExecutionError> {
+        Err(ExecutionError::UnsupportedStep { step: "scDeploy" })
+    }
+}
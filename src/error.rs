@@ -0,0 +1,364 @@
+use super::source_location::*;
+use super::value_formatter::*;
+use super::state_check::*;
+use super::tx_check::*;
+use num_bigint::BigUint;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Describes why a Mandos value could not be interpreted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MandosError {
+    /// The digits following `0x` are not valid hex.
+    InvalidHex(String),
+
+    /// The digits following `0b` are not valid binary.
+    InvalidBinary(String),
+
+    /// The value has no known prefix and is not a base 10 number.
+    InvalidDecimal(String),
+
+    /// The number does not fit in the fixed width requested by its prefix (e.g. `u8:256`).
+    FixedWidthOverflow {
+        value: String,
+        width: usize,
+    },
+
+    /// The value does not fit in the integer type it is interpreted as.
+    IntegerOverflow {
+        value: String,
+        type_name: &'static str,
+    },
+
+    /// `biguint:` only takes unsigned numbers, not e.g. `-1` or `i8:5`.
+    SignedBigUint(String),
+
+    /// Booleans must interpret to either nothing or `0x01`.
+    InvalidBool(String),
+
+    /// Not a valid `erd1...` bech32 address.
+    InvalidBech32 {
+        value: String,
+        reason: String,
+    },
+
+    /// Addresses must be exactly 32 bytes long.
+    InvalidAddressLength {
+        value: String,
+        length: usize,
+    },
+
+    /// Two keys of the same map are different spellings of the same value.
+    DuplicateKey {
+        first: String,
+        second: String,
+    },
+
+    /// The value starts with a prefix that is neither built-in nor registered in the context.
+    UnknownPrefix {
+        prefix: String,
+        value: String,
+    },
+
+    /// Raised by user-defined prefix handlers.
+    Custom(String),
+
+    /// The file referenced by a `file:` value could not be read. The path is the resolved one.
+    FileRead {
+        path: PathBuf,
+        error: String,
+    },
+
+    /// The value is a `file:` that was left to be loaded later, with lazy file loading,
+    /// and was used without loading it. The path is the resolved one.
+    FileNotLoaded(PathBuf),
+
+    /// Any of the above, together with the place in the scenario file where the value was written.
+    Located {
+        location: SourceLocation,
+        error: Box<MandosError>,
+    },
+}
+
+impl MandosError {
+    /// Attaches a source location, if known and unless the error already has one.
+    pub fn at(self, location: Option<&SourceLocation>) -> Self {
+        match (self, location) {
+            (error @ MandosError::Located { .. }, _) => error,
+            (error, Some(location)) => MandosError::Located {
+                location: location.clone(),
+                error: Box::new(error),
+            },
+            (error, None) => error,
+        }
+    }
+}
+
+impl Locatable for MandosError {
+    fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            MandosError::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for MandosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MandosError::InvalidHex(s) =>
+                write!(f, "could not parse hex number: {}", s),
+            MandosError::InvalidBinary(s) =>
+                write!(f, "could not parse binary number: {}", s),
+            MandosError::InvalidDecimal(s) =>
+                write!(f, "could not parse base 10 number: {}", s),
+            MandosError::FixedWidthOverflow { value, width } =>
+                write!(f, "representation of {} does not fit in {} bytes", value, width),
+            MandosError::IntegerOverflow { value, type_name } =>
+                write!(f, "value {} does not fit in a {}", value, type_name),
+            MandosError::SignedBigUint(s) =>
+                write!(f, "biguint value {} must be unsigned", s),
+            MandosError::InvalidBool(s) =>
+                write!(f, "value {} is not a valid boolean", s),
+            MandosError::InvalidBech32 { value, reason } =>
+                write!(f, "invalid bech32 address {}: {}", value, reason),
+            MandosError::InvalidAddressLength { value, length } =>
+                write!(f, "address {} is {} bytes long, expected 32", value, length),
+            MandosError::DuplicateKey { first, second } =>
+                write!(f, "keys {} and {} have the same value", first, second),
+            MandosError::UnknownPrefix { prefix, value } =>
+                write!(f, "unknown value prefix `{}` in {}", prefix, value),
+            MandosError::Custom(message) =>
+                write!(f, "{}", message),
+            MandosError::FileRead { path, error } =>
+                write!(f, "could not read file {}: {}", path.display(), error),
+            MandosError::FileNotLoaded(path) =>
+                write!(f, "file {} was not loaded", path.display()),
+            MandosError::Located { location, error } =>
+                write!(f, "{}: {}", location, error),
+        }
+    }
+}
+
+impl Error for MandosError {}
+
+/// Everything that can go wrong while loading a scenario file.
+#[derive(Debug)]
+pub enum ParseScenarioError {
+    /// The file could not be read.
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+
+    /// The file is not valid JSON.
+    Syntax {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// The file is valid JSON, but not a valid scenario (e.g. an unknown `step` tag or a missing field).
+    Schema {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// The scenario contains a field that is not part of the Mandos format,
+    /// and the parse was asked to reject those. `field` is a path such as `steps[2].tx.gasLimt`,
+    /// `line` and `column` are those of its key.
+    UnknownField {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        field: String,
+        suggestion: Option<String>,
+    },
+
+    /// One of the values in the scenario could not be interpreted.
+    Interpret(MandosError),
+
+    /// A file reached through `externalSteps` includes itself, directly or not.
+    /// The chain starts with the top-level file and ends with the repeated one.
+    IncludeCycle {
+        chain: Vec<PathBuf>,
+    },
+
+    /// A file reached through `externalSteps` could not be loaded.
+    /// The chain starts with the top-level file and ends with the file containing the `externalSteps` step.
+    Included {
+        chain: Vec<PathBuf>,
+        error: Box<ParseScenarioError>,
+    },
+}
+
+impl ParseScenarioError {
+    pub(crate) fn from_json(path: PathBuf, error: serde_json::Error) -> Self {
+        let (line, column) = (error.line(), error.column());
+        let full_message = error.to_string();
+        let location_suffix = format!(" at line {} column {}", line, column);
+        let message = full_message
+            .strip_suffix(location_suffix.as_str())
+            .unwrap_or(full_message.as_str())
+            .to_string();
+        match error.classify() {
+            serde_json::error::Category::Data => ParseScenarioError::Schema { path, line, column, message },
+            serde_json::error::Category::Io => ParseScenarioError::Io { path, error: error.into() },
+            _ => ParseScenarioError::Syntax { path, line, column, message },
+        }
+    }
+}
+
+impl fmt::Display for ParseScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseScenarioError::Io { path, error } =>
+                write!(f, "{}: could not read file: {}", path.display(), error),
+            ParseScenarioError::Syntax { path, line, column, message } =>
+                write!(f, "{}:{}:{}: invalid JSON: {}", path.display(), line, column, message),
+            ParseScenarioError::Schema { path, line, column, message } =>
+                write!(f, "{}:{}:{}: invalid scenario: {}", path.display(), line, column, message),
+            ParseScenarioError::UnknownField { path, line, column, field, suggestion: Some(suggestion) } =>
+                write!(f, "{}:{}:{}: unknown field {}, did you mean `{}`?", path.display(), line, column, field, suggestion),
+            ParseScenarioError::UnknownField { path, line, column, field, suggestion: None } =>
+                write!(f, "{}:{}:{}: unknown field {}", path.display(), line, column, field),
+            ParseScenarioError::Interpret(error) =>
+                error.fmt(f),
+            ParseScenarioError::IncludeCycle { chain } =>
+                write!(f, "externalSteps include cycle: {}", display_chain(chain)),
+            ParseScenarioError::Included { chain, error } =>
+                write!(f, "{} (included from {})", error, display_chain(chain)),
+        }
+    }
+}
+
+impl Error for ParseScenarioError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseScenarioError::Io { error, .. } => Some(error),
+            ParseScenarioError::Interpret(error) => Some(error),
+            ParseScenarioError::Included { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Everything that can go wrong while executing a step against a world state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecutionError {
+    /// The transaction involves an account that is not in the world state.
+    UnknownAccount {
+        address: [u8; 32],
+    },
+
+    /// The sender's balance is less than the value transferred.
+    InsufficientFunds {
+        address: [u8; 32],
+        balance: BigUint,
+        value: BigUint,
+    },
+
+    /// The step cannot be executed by the world state alone.
+    UnsupportedStep {
+        step: &'static str,
+    },
+
+    /// A `checkState` step does not match the world state. All differences are listed.
+    StateMismatch {
+        mismatches: Vec<StateMismatch>,
+    },
+
+    /// The result of a contract call or deploy does not match its `expect`. All differences are listed.
+    TxMismatch {
+        mismatches: Vec<TxMismatch>,
+    },
+
+    /// A value of the step could not be used.
+    Interpret(MandosError),
+
+    /// Raised by contract executors.
+    Custom(String),
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::UnknownAccount { address } =>
+                write!(f, "unknown account {}", format_value(address, FormatHint::Address)),
+            ExecutionError::InsufficientFunds { address, balance, value } =>
+                write!(f, "insufficient funds: account {} has {}, needs {}",
+                    format_value(address, FormatHint::Address), balance, value),
+            ExecutionError::UnsupportedStep { step } =>
+                write!(f, "{} steps are not supported", step),
+            ExecutionError::StateMismatch { mismatches } => {
+                write!(f, "check state failed with {} mismatch(es)", mismatches.len())?;
+                for mismatch in mismatches.iter() {
+                    write!(f, "\n  - {}", mismatch)?;
+                }
+                Ok(())
+            },
+            ExecutionError::TxMismatch { mismatches } => {
+                write!(f, "transaction result check failed with {} mismatch(es)", mismatches.len())?;
+                for mismatch in mismatches.iter() {
+                    write!(f, "\n  - {}", mismatch)?;
+                }
+                Ok(())
+            },
+            ExecutionError::Interpret(error) =>
+                error.fmt(f),
+            ExecutionError::Custom(message) =>
+                write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for ExecutionError {}
+
+/// A step of a scenario could not be run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunScenarioError {
+    /// Position of the step in the scenario, starting at 0.
+    pub step_index: usize,
+
+    /// The file the step was written in, if the scenario was resolved from several files.
+    pub file: Option<PathBuf>,
+    pub tx_id: Option<String>,
+    pub error: Box<ExecutionError>,
+}
+
+impl fmt::Display for RunScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        write!(f, "step {}", self.step_index)?;
+        if let Some(tx_id) = &self.tx_id {
+            write!(f, " (txId {})", tx_id)?;
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl Error for RunScenarioError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+fn display_chain(chain: &[PathBuf]) -> String {
+    chain.iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+impl From<MandosError> for ParseScenarioError {
+    fn from(error: MandosError) -> Self {
+        ParseScenarioError::Interpret(error)
+    }
+}
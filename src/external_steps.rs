@@ -0,0 +1,113 @@
+use super::scenario::*;
+use super::value::InterpretableFrom;
+use super::context::*;
+use super::error::*;
+use super::parse_util::*;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A step of a scenario whose `externalSteps` have been resolved,
+/// together with the file it was written in.
+#[derive(Debug)]
+pub struct ResolvedStep {
+    pub file: PathBuf,
+    pub step: Step,
+}
+
+/// A scenario with all `externalSteps` replaced, recursively, by the steps of the files they point to.
+/// Name, comment and `checkGas` are those of the top-level file.
+#[derive(Debug)]
+pub struct ResolvedScenario {
+    pub name: Option<String>,
+    pub comment: Option<String>,
+    pub check_gas: Option<bool>,
+    pub steps: Vec<ResolvedStep>,
+}
+
+pub fn try_resolve_scenario<P: AsRef<Path>>(path: P) -> Result<ResolvedScenario, ParseScenarioError> {
+    try_resolve_scenario_with(path, |file| InterpreterContext::for_scenario_file(file))
+}
+
+/// Same as `try_resolve_scenario`, but every file, including the top-level one,
+/// is parsed and interpreted in the context returned by `make_context` for its path.
+pub fn try_resolve_scenario_with<P, F>(path: P, make_context: F) -> Result<ResolvedScenario, ParseScenarioError>
+where
+    P: AsRef<Path>,
+    F: Fn(&Path) -> InterpreterContext,
+{
+    let path = path.as_ref();
+    let scenario = interpret_file(path, &make_context)?;
+    let mut steps = Vec::new();
+    let mut include_chain = vec![path.to_path_buf()];
+    resolve_steps(path, scenario.steps, &make_context, &mut include_chain, &mut steps)?;
+    Ok(ResolvedScenario {
+        name: scenario.name,
+        comment: scenario.comment,
+        check_gas: scenario.check_gas,
+        steps,
+    })
+}
+
+pub fn resolve_scenario<P: AsRef<Path>>(path: P) -> ResolvedScenario {
+    try_resolve_scenario(path).unwrap()
+}
+
+fn interpret_file<F>(path: &Path, make_context: &F) -> Result<Scenario, ParseScenarioError>
+where
+    F: Fn(&Path) -> InterpreterContext,
+{
+    let context = make_context(path);
+    let raw = try_parse_scenario_raw_with_policy(path, context.unknown_field_policy())?;
+    Ok(Scenario::interpret_from(raw, &context)?)
+}
+
+/// `include_chain` holds the files currently being resolved, starting with the top-level one.
+fn resolve_steps<F>(
+    file: &Path,
+    steps: Vec<Step>,
+    make_context: &F,
+    include_chain: &mut Vec<PathBuf>,
+    resolved: &mut Vec<ResolvedStep>,
+) -> Result<(), ParseScenarioError>
+where
+    F: Fn(&Path) -> InterpreterContext,
+{
+    for step in steps {
+        let external_path = match step {
+            Step::ExternalSteps { path, .. } => path,
+            step => {
+                resolved.push(ResolvedStep {
+                    file: file.to_path_buf(),
+                    step,
+                });
+                continue;
+            },
+        };
+
+        let external_file = file.parent().unwrap_or_else(|| Path::new("")).join(external_path);
+        if include_chain.iter().any(|included| same_file(included, &external_file)) {
+            let mut chain = include_chain.clone();
+            chain.push(external_file);
+            return Err(ParseScenarioError::IncludeCycle { chain });
+        }
+
+        let scenario = interpret_file(&external_file, make_context)
+            .map_err(|error| ParseScenarioError::Included {
+                chain: include_chain.clone(),
+                error: Box::new(error),
+            })?;
+        include_chain.push(external_file.clone());
+        resolve_steps(&external_file, scenario.steps, make_context, include_chain, resolved)?;
+        include_chain.pop();
+    }
+    Ok(())
+}
+
+/// Different spellings of the same path, such as `a/../b.scen.json` and `b.scen.json`, are the same file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
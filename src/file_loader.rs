@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Source of the contents of `file:` values.
+pub trait FileLoader {
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>>;
+}
+
+/// Reads files from disk. This is the default.
+#[derive(Default)]
+pub struct DiskFileLoader;
+
+impl FileLoader for DiskFileLoader {
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+}
+
+/// Serves files from memory, mostly useful in tests.
+#[derive(Default)]
+pub struct InMemoryFileLoader {
+    files: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl InMemoryFileLoader {
+    pub fn new() -> Self {
+        InMemoryFileLoader::default()
+    }
+
+    pub fn with_file<P: AsRef<Path>>(mut self, path: P, contents: &[u8]) -> Self {
+        self.add_file(path, contents);
+        self
+    }
+
+    pub fn add_file<P: AsRef<Path>>(&mut self, path: P, contents: &[u8]) {
+        self.files.insert(path.as_ref().to_path_buf(), contents.to_vec());
+    }
+}
+
+impl FileLoader for InMemoryFileLoader {
+    fn load(&self, path: &Path) -> io::Result<Vec<u8>> {
+        self.files.get(path).cloned().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "file not found in memory")
+        })
+    }
+}
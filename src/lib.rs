@@ -11,7 +11,22 @@ mod value_raw;
 mod logs;
 mod logs_raw;
 mod parse_util;
+mod write_util;
 mod context;
+mod error;
+mod source_location;
+mod file_loader;
+mod value_prefix;
+mod bech32_address;
+mod value_formatter;
+mod unknown_fields;
+mod external_steps;
+mod world_state;
+mod step_executor;
+mod state_check;
+mod contract_executor;
+mod scenario_runner;
+mod tx_check;
 
 pub use scenario::*;
 pub use scenario_raw::*;
@@ -25,4 +40,18 @@ pub use value_raw::*;
 pub use logs::*;
 pub use logs_raw::*;
 pub use parse_util::*;
+pub use write_util::*;
 pub use context::*;
+pub use error::*;
+pub use source_location::*;
+pub use file_loader::*;
+pub use value_prefix::*;
+pub use bech32_address::*;
+pub use value_formatter::*;
+pub use unknown_fields::*;
+pub use external_steps::*;
+pub use world_state::*;
+pub use state_check::*;
+pub use contract_executor::*;
+pub use scenario_runner::*;
+pub use tx_check::*;
@@ -1,25 +1,39 @@
 use super::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct CheckLog {
     pub address: BytesValue,
     pub identifier: BytesValue,
     pub topics: Vec<BytesValue>,
     pub data: BytesValue,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<CheckLogRaw> for CheckLog {
-    fn interpret_from(from: CheckLogRaw, context: &InterpreterContext) -> Self {
-        CheckLog {
-            address: BytesValue::interpret_from(from.address, context),
-            identifier: BytesValue::interpret_from(from.identifier, context),
-            topics: from.topics.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect(),
-            data: BytesValue::interpret_from(from.data, context),
+    fn interpret_from(from: CheckLogRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(CheckLog {
+            address: BytesValue::interpret_from(from.address, context)?,
+            identifier: BytesValue::interpret_from(from.identifier, context)?,
+            topics: from.topics.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect::<Result<_, _>>()?,
+            data: BytesValue::interpret_from(from.data, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<CheckLogRaw> for CheckLog {
+    fn into_raw(self) -> CheckLogRaw {
+        CheckLogRaw {
+            address: self.address.into_raw(),
+            identifier: self.identifier.into_raw(),
+            topics: self.topics.into_iter().map(IntoRaw::into_raw).collect(),
+            data: self.data.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum CheckLogs {
     Star,
     List(Vec<CheckLog>),
@@ -33,13 +47,23 @@ impl CheckLogs {
 }
 
 impl InterpretableFrom<CheckLogsRaw> for CheckLogs {
-    fn interpret_from(from: CheckLogsRaw, context: &InterpreterContext) -> Self {
-        match from {
+    fn interpret_from(from: CheckLogsRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(match from {
             CheckLogsRaw::Star => CheckLogs::Star,
             CheckLogsRaw::List(l) => CheckLogs::List(
-                l.into_iter().map(|c| CheckLog::interpret_from(c, context)).collect()
+                l.into_iter().map(|c| CheckLog::interpret_from(c, context)).collect::<Result<_, _>>()?
             ),
             CheckLogsRaw::DefaultStar => CheckLogs::DefaultStar
+        })
+    }
+}
+
+impl IntoRaw<CheckLogsRaw> for CheckLogs {
+    fn into_raw(self) -> CheckLogsRaw {
+        match self {
+            CheckLogs::Star => CheckLogsRaw::Star,
+            CheckLogs::List(l) => CheckLogsRaw::List(l.into_iter().map(IntoRaw::into_raw).collect()),
+            CheckLogs::DefaultStar => CheckLogsRaw::DefaultStar,
         }
     }
 }
@@ -15,11 +15,16 @@ pub struct CheckLogRaw {
     pub topics: Vec<ValueSubTree>,
 
     pub data: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
+#[derive(Default)]
 pub enum CheckLogsRaw {
     Star,
     List(Vec<CheckLogRaw>),
+    #[default]
     DefaultStar,
 }
 
@@ -33,12 +38,6 @@ impl CheckLogsRaw {
     }
 }
 
-impl Default for CheckLogsRaw {
-    fn default() -> Self {
-        CheckLogsRaw::DefaultStar
-    }
-}
-
 impl Serialize for CheckLogsRaw {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -72,7 +71,7 @@ impl<'de> Visitor<'de> for CheckLogsVisitor {
         E: de::Error,
     {
         if value == "*" {
-            Ok(CheckLogsRaw::Star)    
+            Ok(CheckLogsRaw::Star)
         } else {
             Err(de::Error::custom("only '*' allowed as logs string value"))
         }
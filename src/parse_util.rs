@@ -2,16 +2,57 @@ use super::scenario::*;
 use super::scenario_raw::*;
 use super::value::InterpretableFrom;
 use super::context::*;
+use super::error::*;
+use super::source_location::*;
+use super::unknown_fields::*;
 
 use std::fs;
 use std::path::Path;
 
+pub fn try_parse_scenario_raw<P: AsRef<Path>>(path: P) -> Result<ScenarioRaw, ParseScenarioError> {
+    try_parse_scenario_raw_with_policy(path, UnknownFieldPolicy::Keep)
+}
+
+pub fn try_parse_scenario_raw_with_policy<P: AsRef<Path>>(
+    path: P,
+    unknown_fields: UnknownFieldPolicy,
+) -> Result<ScenarioRaw, ParseScenarioError> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+        .map_err(|error| ParseScenarioError::Io { path: path.to_path_buf(), error })?;
+
+    let source = SourceMap::scan(&contents, Some(path));
+    let parsed = serde_json::from_str(contents.as_str())
+        .map(|mut raw: ScenarioRaw| {
+            raw.locate_values("", &source);
+            raw
+        })
+        .map_err(|error| ParseScenarioError::from_json(path.to_path_buf(), error));
+
+    match unknown_fields {
+        UnknownFieldPolicy::Keep => parsed,
+        UnknownFieldPolicy::Reject => reject_unknown_fields(path, &source, parsed),
+    }
+}
+
+pub fn try_parse_scenario<P: AsRef<Path>>(path: P) -> Result<Scenario, ParseScenarioError> {
+    let context = InterpreterContext::for_scenario_file(path.as_ref());
+    try_parse_scenario_with(path, &context)
+}
+
+/// Same as `try_parse_scenario`, but parsed with the unknown field policy of `context` and interpreted in it.
+pub fn try_parse_scenario_with<P: AsRef<Path>>(
+    path: P,
+    context: &InterpreterContext,
+) -> Result<Scenario, ParseScenarioError> {
+    let raw = try_parse_scenario_raw_with_policy(path, context.unknown_field_policy())?;
+    Ok(Scenario::interpret_from(raw, context)?)
+}
+
 pub fn parse_scenario_raw<P: AsRef<Path>>(path: P) -> ScenarioRaw {
-    let contents = fs::read_to_string(path).unwrap();
-    serde_json::from_str(contents.as_str()).unwrap()
+    try_parse_scenario_raw(path).unwrap()
 }
 
 pub fn parse_scenario<P: AsRef<Path>>(path: P) -> Scenario {
-    let raw = parse_scenario_raw(path);
-    Scenario::interpret_from(raw, &InterpreterContext::default())
+    try_parse_scenario(path).unwrap()
 }
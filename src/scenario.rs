@@ -1,5 +1,5 @@
 use super::*;
-use std::collections::BTreeMap;
+use indexmap::IndexMap;
 
 #[derive(Debug)]
 pub struct Scenario {
@@ -7,15 +7,29 @@ pub struct Scenario {
     pub comment: Option<String>,
     pub check_gas: Option<bool>,
     pub steps: Vec<Step>,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<ScenarioRaw> for Scenario {
-    fn interpret_from(from: ScenarioRaw, context: &InterpreterContext) -> Self {
-        Scenario {
+    fn interpret_from(from: ScenarioRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(Scenario {
             name: from.name,
             comment: from.comment,
             check_gas: from.check_gas,
-            steps: from.steps.into_iter().map(|s| Step::interpret_from(s, context)).collect(),
+            steps: from.steps.into_iter().map(|s| Step::interpret_from(s, context)).collect::<Result<_, _>>()?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<ScenarioRaw> for Scenario {
+    fn into_raw(self) -> ScenarioRaw {
+        ScenarioRaw {
+            name: self.name,
+            comment: self.comment,
+            check_gas: self.check_gas,
+            steps: self.steps.into_iter().map(IntoRaw::into_raw).collect(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -24,53 +38,63 @@ impl InterpretableFrom<ScenarioRaw> for Scenario {
 pub enum Step {
     ExternalSteps {
         path: String,
+        unknown_fields: UnknownFields,
     },
     SetState {
         comment: Option<String>,
-        accounts: BTreeMap<AddressKey, Account>,
+        accounts: IndexMap<AddressKey, Account>,
         new_addresses: Vec<NewAddress>,
         block_hashes: Vec<BytesValue>,
         previous_block_info: Option<BlockInfo>,
         current_block_info: Option<BlockInfo>,
+        unknown_fields: UnknownFields,
     },
     ScCall {
         tx_id: String,
         comment: Option<String>,
         tx: TxCall,
         expect: Option<TxExpect>,
+        unknown_fields: UnknownFields,
     },
     ScDeploy {
         tx_id: String,
         comment: Option<String>,
         tx: TxDeploy,
         expect: Option<TxExpect>,
+        unknown_fields: UnknownFields,
     },
     Transfer {
         tx_id: String,
         comment: Option<String>,
         tx: TxTransfer,
+        unknown_fields: UnknownFields,
     },
     ValidatorReward {
         tx_id: String,
         comment: Option<String>,
         tx: TxValidatorReward,
+        unknown_fields: UnknownFields,
     },
     CheckState {
         comment: Option<String>,
         accounts: CheckAccounts,
+        unknown_fields: UnknownFields,
     },
     DumpState {
         comment: Option<String>,
+        unknown_fields: UnknownFields,
     },
 }
 
 impl InterpretableFrom<StepRaw> for Step {
-    fn interpret_from(from: StepRaw, context: &InterpreterContext) -> Self {
-        match from {
+    fn interpret_from(from: StepRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(match from {
             StepRaw::ExternalSteps {
                 path,
+                unknown_fields,
             } => Step::ExternalSteps {
-                path
+                path,
+                unknown_fields,
             },
             StepRaw::SetState {
                 comment,
@@ -79,67 +103,174 @@ impl InterpretableFrom<StepRaw> for Step {
                 block_hashes,
                 previous_block_info,
                 current_block_info,
+                unknown_fields,
             } => Step::SetState {
                 comment,
-                accounts: accounts.into_iter().map(|(k, v)| (
-                    AddressKey::interpret_from(k, context),
-                    Account::interpret_from(v, context))).collect(),
-                new_addresses: new_addresses.into_iter().map(|t| NewAddress::interpret_from(t, context)).collect(),
-                block_hashes: block_hashes.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect(),
-                previous_block_info: previous_block_info.map(|v| BlockInfo::interpret_from(v, context)),
-                current_block_info: current_block_info.map(|v| BlockInfo::interpret_from(v, context)),
+                accounts: interpret_map(accounts, context)?,
+                new_addresses: new_addresses.into_iter().map(|t| NewAddress::interpret_from(t, context)).collect::<Result<_, _>>()?,
+                block_hashes: block_hashes.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect::<Result<_, _>>()?,
+                previous_block_info: previous_block_info.map(|v| BlockInfo::interpret_from(v, context)).transpose()?,
+                current_block_info: current_block_info.map(|v| BlockInfo::interpret_from(v, context)).transpose()?,
+                unknown_fields,
             },
             StepRaw::ScCall {
                 tx_id,
                 comment,
                 tx,
                 expect,
+                unknown_fields,
             } => Step::ScCall {
                 tx_id,
                 comment,
-                tx: TxCall::interpret_from(tx, context),
-                expect: expect.map(|v| TxExpect::interpret_from(v, context)),
+                tx: TxCall::interpret_from(tx, context)?,
+                expect: expect.map(|v| TxExpect::interpret_from(v, context)).transpose()?,
+                unknown_fields,
             },
             StepRaw::ScDeploy {
                 tx_id,
                 comment,
                 tx,
                 expect,
+                unknown_fields,
             } => Step::ScDeploy {
                 tx_id,
                 comment,
-                tx: TxDeploy::interpret_from(tx, context),
-                expect: expect.map(|v| TxExpect::interpret_from(v, context)),
+                tx: TxDeploy::interpret_from(tx, context)?,
+                expect: expect.map(|v| TxExpect::interpret_from(v, context)).transpose()?,
+                unknown_fields,
             },
             StepRaw::Transfer {
                 tx_id,
                 comment,
                 tx,
+                unknown_fields,
             } => Step::Transfer {
                 tx_id,
                 comment,
-                tx: TxTransfer::interpret_from(tx, context),
+                tx: TxTransfer::interpret_from(tx, context)?,
+                unknown_fields,
             },
             StepRaw::ValidatorReward {
                 tx_id,
                 comment,
                 tx,
+                unknown_fields,
             } => Step::ValidatorReward {
                 tx_id,
                 comment,
-                tx: TxValidatorReward::interpret_from(tx, context),
+                tx: TxValidatorReward::interpret_from(tx, context)?,
+                unknown_fields,
             },
             StepRaw::CheckState {
                 comment,
                 accounts,
+                unknown_fields,
             } => Step::CheckState {
                 comment,
-                accounts: CheckAccounts::interpret_from(accounts, context),
+                accounts: CheckAccounts::interpret_from(accounts, context)?,
+                unknown_fields,
             },
             StepRaw::DumpState {
                 comment,
+                unknown_fields,
             } => Step::DumpState {
                 comment,
+                unknown_fields,
+            },
+        })
+    }
+}
+
+impl IntoRaw<StepRaw> for Step {
+    fn into_raw(self) -> StepRaw {
+        match self {
+            Step::ExternalSteps {
+                path,
+                unknown_fields,
+            } => StepRaw::ExternalSteps {
+                path,
+                unknown_fields,
+            },
+            Step::SetState {
+                comment,
+                accounts,
+                new_addresses,
+                block_hashes,
+                previous_block_info,
+                current_block_info,
+                unknown_fields,
+            } => StepRaw::SetState {
+                comment,
+                accounts: into_raw_map(accounts),
+                new_addresses: new_addresses.into_iter().map(IntoRaw::into_raw).collect(),
+                block_hashes: block_hashes.into_iter().map(IntoRaw::into_raw).collect(),
+                previous_block_info: previous_block_info.map(IntoRaw::into_raw),
+                current_block_info: current_block_info.map(IntoRaw::into_raw),
+                unknown_fields,
+            },
+            Step::ScCall {
+                tx_id,
+                comment,
+                tx,
+                expect,
+                unknown_fields,
+            } => StepRaw::ScCall {
+                tx_id,
+                comment,
+                tx: tx.into_raw(),
+                expect: expect.map(IntoRaw::into_raw),
+                unknown_fields,
+            },
+            Step::ScDeploy {
+                tx_id,
+                comment,
+                tx,
+                expect,
+                unknown_fields,
+            } => StepRaw::ScDeploy {
+                tx_id,
+                comment,
+                tx: tx.into_raw(),
+                expect: expect.map(IntoRaw::into_raw),
+                unknown_fields,
+            },
+            Step::Transfer {
+                tx_id,
+                comment,
+                tx,
+                unknown_fields,
+            } => StepRaw::Transfer {
+                tx_id,
+                comment,
+                tx: tx.into_raw(),
+                unknown_fields,
+            },
+            Step::ValidatorReward {
+                tx_id,
+                comment,
+                tx,
+                unknown_fields,
+            } => StepRaw::ValidatorReward {
+                tx_id,
+                comment,
+                tx: tx.into_raw(),
+                unknown_fields,
+            },
+            Step::CheckState {
+                comment,
+                accounts,
+                unknown_fields,
+            } => StepRaw::CheckState {
+                comment,
+                accounts: accounts.into_raw(),
+                unknown_fields,
+            },
+            Step::DumpState {
+                comment,
+                unknown_fields,
+            } => StepRaw::DumpState {
+                comment,
+                unknown_fields,
             },
         }
     }
@@ -150,14 +281,27 @@ pub struct NewAddress {
     pub creator_address: AddressValue,
     pub creator_nonce: U64Value,
     pub new_address: AddressValue,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<NewAddressRaw> for NewAddress {
-    fn interpret_from(from: NewAddressRaw, context: &InterpreterContext) -> Self {
-        NewAddress {
-            creator_address: AddressValue::interpret_from(from.creator_address, context),
-            creator_nonce: U64Value::interpret_from(from.creator_nonce, context),
-            new_address: AddressValue::interpret_from(from.new_address, context),
+    fn interpret_from(from: NewAddressRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(NewAddress {
+            creator_address: AddressValue::interpret_from(from.creator_address, context)?,
+            creator_nonce: U64Value::interpret_from(from.creator_nonce, context)?,
+            new_address: AddressValue::interpret_from(from.new_address, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<NewAddressRaw> for NewAddress {
+    fn into_raw(self) -> NewAddressRaw {
+        NewAddressRaw {
+            creator_address: self.creator_address.into_raw(),
+            creator_nonce: self.creator_nonce.into_raw(),
+            new_address: self.new_address.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -168,15 +312,29 @@ pub struct BlockInfo {
     pub block_nonce: Option<U64Value>,
     pub block_round: Option<U64Value>,
     pub block_epoch: Option<U64Value>,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<BlockInfoRaw> for BlockInfo {
-    fn interpret_from(from: BlockInfoRaw, context: &InterpreterContext) -> Self {
-        BlockInfo {
-            block_timestamp: from.block_timestamp.map(|v| U64Value::interpret_from(v, context)),
-            block_nonce: from.block_nonce.map(|v| U64Value::interpret_from(v, context)),
-            block_round: from.block_round.map(|v| U64Value::interpret_from(v, context)),
-            block_epoch: from.block_epoch.map(|v| U64Value::interpret_from(v, context)),
+    fn interpret_from(from: BlockInfoRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(BlockInfo {
+            block_timestamp: from.block_timestamp.map(|v| U64Value::interpret_from(v, context)).transpose()?,
+            block_nonce: from.block_nonce.map(|v| U64Value::interpret_from(v, context)).transpose()?,
+            block_round: from.block_round.map(|v| U64Value::interpret_from(v, context)).transpose()?,
+            block_epoch: from.block_epoch.map(|v| U64Value::interpret_from(v, context)).transpose()?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<BlockInfoRaw> for BlockInfo {
+    fn into_raw(self) -> BlockInfoRaw {
+        BlockInfoRaw {
+            block_timestamp: self.block_timestamp.map(IntoRaw::into_raw),
+            block_nonce: self.block_nonce.map(IntoRaw::into_raw),
+            block_round: self.block_round.map(IntoRaw::into_raw),
+            block_epoch: self.block_epoch.map(IntoRaw::into_raw),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -190,18 +348,35 @@ pub struct TxCall {
     pub arguments: Vec<BytesValue>,
     pub gas_limit: U64Value,
     pub gas_price: U64Value,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<TxCallRaw> for TxCall {
-    fn interpret_from(from: TxCallRaw, context: &InterpreterContext) -> Self {
-        TxCall {
-            from: AddressValue::interpret_from(from.from, context),
-            to: AddressValue::interpret_from(from.to, context),
-            call_value: BigUintValue::interpret_from(from.value, context),
+    fn interpret_from(from: TxCallRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(TxCall {
+            from: AddressValue::interpret_from(from.from, context)?,
+            to: AddressValue::interpret_from(from.to, context)?,
+            call_value: BigUintValue::interpret_from(from.value, context)?,
             function: from.function,
-            arguments: from.arguments.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect(),
-            gas_limit: U64Value::interpret_from(from.gas_limit, context),
-            gas_price: U64Value::interpret_from(from.gas_price, context),
+            arguments: from.arguments.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect::<Result<_, _>>()?,
+            gas_limit: U64Value::interpret_from(from.gas_limit, context)?,
+            gas_price: U64Value::interpret_from(from.gas_price, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<TxCallRaw> for TxCall {
+    fn into_raw(self) -> TxCallRaw {
+        TxCallRaw {
+            from: self.from.into_raw(),
+            to: self.to.into_raw(),
+            value: self.call_value.into_raw(),
+            function: self.function,
+            arguments: self.arguments.into_iter().map(IntoRaw::into_raw).collect(),
+            gas_limit: self.gas_limit.into_raw(),
+            gas_price: self.gas_price.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -214,17 +389,33 @@ pub struct TxDeploy {
     pub arguments: Vec<BytesValue>,
     pub gas_limit: U64Value,
     pub gas_price: U64Value,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<TxDeployRaw> for TxDeploy {
-    fn interpret_from(from: TxDeployRaw, context: &InterpreterContext) -> Self {
-        TxDeploy {
-            from: AddressValue::interpret_from(from.from, context),
-            call_value: BigUintValue::interpret_from(from.value, context),
-            contract_code: BytesValue::interpret_from(from.contract_code, context),
-            arguments: from.arguments.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect(),
-            gas_limit: U64Value::interpret_from(from.gas_limit, context),
-            gas_price: U64Value::interpret_from(from.gas_price, context),
+    fn interpret_from(from: TxDeployRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(TxDeploy {
+            from: AddressValue::interpret_from(from.from, context)?,
+            call_value: BigUintValue::interpret_from(from.value, context)?,
+            contract_code: BytesValue::interpret_from(from.contract_code, context)?,
+            arguments: from.arguments.into_iter().map(|t| BytesValue::interpret_from(t, context)).collect::<Result<_, _>>()?,
+            gas_limit: U64Value::interpret_from(from.gas_limit, context)?,
+            gas_price: U64Value::interpret_from(from.gas_price, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<TxDeployRaw> for TxDeploy {
+    fn into_raw(self) -> TxDeployRaw {
+        TxDeployRaw {
+            from: self.from.into_raw(),
+            value: self.call_value.into_raw(),
+            contract_code: self.contract_code.into_raw(),
+            arguments: self.arguments.into_iter().map(IntoRaw::into_raw).collect(),
+            gas_limit: self.gas_limit.into_raw(),
+            gas_price: self.gas_price.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -234,14 +425,27 @@ pub struct TxTransfer {
     pub from: AddressValue,
     pub to: AddressValue,
     pub value: BigUintValue,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<TxTransferRaw> for TxTransfer {
-    fn interpret_from(from: TxTransferRaw, context: &InterpreterContext) -> Self {
-        TxTransfer {
-            from: AddressValue::interpret_from(from.from, context),
-            to: AddressValue::interpret_from(from.to, context),
-            value: BigUintValue::interpret_from(from.value, context),
+    fn interpret_from(from: TxTransferRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(TxTransfer {
+            from: AddressValue::interpret_from(from.from, context)?,
+            to: AddressValue::interpret_from(from.to, context)?,
+            value: BigUintValue::interpret_from(from.value, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<TxTransferRaw> for TxTransfer {
+    fn into_raw(self) -> TxTransferRaw {
+        TxTransferRaw {
+            from: self.from.into_raw(),
+            to: self.to.into_raw(),
+            value: self.value.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -250,13 +454,25 @@ impl InterpretableFrom<TxTransferRaw> for TxTransfer {
 pub struct TxValidatorReward {
     pub to: AddressValue,
     pub value: BigUintValue,
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<TxValidatorRewardRaw> for TxValidatorReward {
-    fn interpret_from(from: TxValidatorRewardRaw, context: &InterpreterContext) -> Self {
-        TxValidatorReward {
-            to: AddressValue::interpret_from(from.to, context),
-            value: BigUintValue::interpret_from(from.value, context),
+    fn interpret_from(from: TxValidatorRewardRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(TxValidatorReward {
+            to: AddressValue::interpret_from(from.to, context)?,
+            value: BigUintValue::interpret_from(from.value, context)?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<TxValidatorRewardRaw> for TxValidatorReward {
+    fn into_raw(self) -> TxValidatorRewardRaw {
+        TxValidatorRewardRaw {
+            to: self.to.into_raw(),
+            value: self.value.into_raw(),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -269,18 +485,33 @@ pub struct TxExpect {
     pub message: Option<BytesValue>,
     pub gas: Option<CheckValue<U64Value>>,
     pub refund: Option<CheckValue<U64Value>>,
-
+    pub unknown_fields: UnknownFields,
 }
 
 impl InterpretableFrom<TxExpectRaw> for TxExpect {
-    fn interpret_from(from: TxExpectRaw, context: &InterpreterContext) -> Self {
-        TxExpect {
-            out: from.out.into_iter().map(|t| CheckValue::<BytesValue>::interpret_from(t, context)).collect(),
-            status: U64Value::interpret_from(from.status, context),
-            logs: CheckLogs::interpret_from(from.logs, context),
-            message: from.message.map(|v| BytesValue::interpret_from(v, context)),
-            gas: from.gas.map(|v| CheckValue::<U64Value>::interpret_from(v, context)),
-            refund: from.refund.map(|v| CheckValue::<U64Value>::interpret_from(v, context)),
+    fn interpret_from(from: TxExpectRaw, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(TxExpect {
+            out: from.out.into_iter().map(|t| CheckValue::<BytesValue>::interpret_from(t, context)).collect::<Result<_, _>>()?,
+            status: U64Value::interpret_from(from.status, context)?,
+            logs: CheckLogs::interpret_from(from.logs, context)?,
+            message: from.message.map(|v| BytesValue::interpret_from(v, context)).transpose()?,
+            gas: from.gas.map(|v| CheckValue::<U64Value>::interpret_from(v, context)).transpose()?,
+            refund: from.refund.map(|v| CheckValue::<U64Value>::interpret_from(v, context)).transpose()?,
+            unknown_fields: from.unknown_fields,
+        })
+    }
+}
+
+impl IntoRaw<TxExpectRaw> for TxExpect {
+    fn into_raw(self) -> TxExpectRaw {
+        TxExpectRaw {
+            out: self.out.into_iter().map(IntoRaw::into_raw).collect(),
+            status: self.status.into_raw(),
+            logs: self.logs.into_raw(),
+            message: self.message.map(IntoRaw::into_raw),
+            gas: self.gas.map(IntoRaw::into_raw),
+            refund: self.refund.map(IntoRaw::into_raw),
+            unknown_fields: self.unknown_fields,
         }
     }
 }
@@ -1,6 +1,6 @@
 use super::*;
 use serde::{Serialize, Deserialize};
-use std::collections::BTreeMap;
+use indexmap::IndexMap;
 
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +17,9 @@ pub struct ScenarioRaw {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub check_gas: Option<bool>,
     pub steps: Vec<StepRaw>,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -25,6 +28,9 @@ pub struct ScenarioRaw {
 pub enum StepRaw {
     ExternalSteps {
         path: String,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
     
     #[serde(rename_all = "camelCase")]
@@ -34,8 +40,8 @@ pub enum StepRaw {
         comment: Option<String>,
 
         #[serde(default)]
-        #[serde(skip_serializing_if = "BTreeMap::is_empty")]
-        accounts: BTreeMap<String, AccountRaw>,
+        #[serde(skip_serializing_if = "IndexMap::is_empty")]
+        accounts: IndexMap<String, AccountRaw>,
         
         #[serde(default)]
         #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -52,6 +58,9 @@ pub enum StepRaw {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         current_block_info: Option<BlockInfoRaw>,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -67,6 +76,9 @@ pub enum StepRaw {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         expect: Option<TxExpectRaw>,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -82,6 +94,9 @@ pub enum StepRaw {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         expect: Option<TxExpectRaw>,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -93,6 +108,9 @@ pub enum StepRaw {
         comment: Option<String>,
 
         tx: TxTransferRaw,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -104,6 +122,9 @@ pub enum StepRaw {
         comment: Option<String>,
 
         tx: TxValidatorRewardRaw,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 
     CheckState {
@@ -112,12 +133,18 @@ pub enum StepRaw {
         comment: Option<String>,
 
         accounts: CheckAccountsRaw,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 
     DumpState {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         comment: Option<String>,
+
+        #[serde(flatten)]
+        unknown_fields: UnknownFields,
     },
 }
 
@@ -127,6 +154,9 @@ pub struct NewAddressRaw {
     pub creator_address: ValueSubTree,
     pub creator_nonce: ValueSubTree,
     pub new_address: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -147,6 +177,9 @@ pub struct BlockInfoRaw {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub block_epoch: Option<ValueSubTree>,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -162,6 +195,9 @@ pub struct TxCallRaw {
 
     pub gas_limit: ValueSubTree,
     pub gas_price: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -177,6 +213,9 @@ pub struct TxDeployRaw {
 
     pub gas_limit: ValueSubTree,
     pub gas_price: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -185,6 +224,9 @@ pub struct TxTransferRaw {
     pub from: ValueSubTree,
     pub to: ValueSubTree,
     pub value: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -192,6 +234,9 @@ pub struct TxTransferRaw {
 pub struct TxValidatorRewardRaw {
     pub to: ValueSubTree,
     pub value: ValueSubTree,
+
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -219,4 +264,6 @@ pub struct TxExpectRaw {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub refund: Option<ValueSubTree>,
 
+    #[serde(flatten)]
+    pub unknown_fields: UnknownFields,
 }
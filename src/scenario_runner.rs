@@ -0,0 +1,114 @@
+use super::contract_executor::*;
+use super::error::*;
+use super::external_steps::*;
+use super::scenario::*;
+use super::world_state::*;
+use std::path::Path;
+
+/// Executes scenario steps against a world state,
+/// handing contract calls and deploys to a `ContractExecutor`.
+pub struct ScenarioRunner<E: ContractExecutor> {
+    executor: E,
+    world: WorldState,
+    check_gas: Option<bool>,
+}
+
+impl<E: ContractExecutor> ScenarioRunner<E> {
+    pub fn new(executor: E) -> Self {
+        ScenarioRunner::with_world(executor, WorldState::new())
+    }
+
+    pub fn with_world(executor: E, world: WorldState) -> Self {
+        ScenarioRunner {
+            executor,
+            world,
+            check_gas: None,
+        }
+    }
+
+    pub fn executor(&self) -> &E {
+        &self.executor
+    }
+
+    pub fn executor_mut(&mut self) -> &mut E {
+        &mut self.executor
+    }
+
+    pub fn world(&self) -> &WorldState {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut WorldState {
+        &mut self.world
+    }
+
+    pub fn into_world(self) -> WorldState {
+        self.world
+    }
+
+    /// Whether `expect.gas` is checked, if set with `set_check_gas`.
+    /// Otherwise `run_scenario` follows the scenario's `checkGas`, and gas is checked when that is missing too.
+    pub fn check_gas(&self) -> Option<bool> {
+        self.check_gas
+    }
+
+    /// Overrides the `checkGas` of the scenarios run afterwards.
+    pub fn set_check_gas(&mut self, check_gas: bool) {
+        self.check_gas = Some(check_gas);
+    }
+
+    /// Runs a single step. Contract calls and deploys return the executor's result,
+    /// after checking it against the step's `expect`, if any.
+    /// `externalSteps` are not followed, resolve the scenario first to run them.
+    pub fn run_step(&mut self, step: &Step) -> Result<Option<TxResult>, ExecutionError> {
+        self.run_step_checking_gas(step, self.check_gas.unwrap_or(true))
+    }
+
+    fn run_step_checking_gas(&mut self, step: &Step, check_gas: bool) -> Result<Option<TxResult>, ExecutionError> {
+        let (result, expect) = match step {
+            Step::ScCall { tx, expect, .. } => (self.executor.sc_call(tx, &mut self.world)?, expect),
+            Step::ScDeploy { tx, expect, .. } => (self.executor.sc_deploy(tx, &mut self.world)?, expect),
+            _ => return self.world.execute_step(step).map(|_| None),
+        };
+        if let Some(expect) = expect {
+            expect.check(&result, check_gas)?;
+        }
+        Ok(Some(result))
+    }
+
+    /// Runs all steps in order, stopping at the first one that fails.
+    pub fn run_scenario(&mut self, scenario: &Scenario) -> Result<(), RunScenarioError> {
+        let check_gas = self.check_gas.or(scenario.check_gas).unwrap_or(true);
+        for (index, step) in scenario.steps.iter().enumerate() {
+            self.run_step_checking_gas(step, check_gas)
+                .map_err(|error| step_error(index, None, step, error))?;
+        }
+        Ok(())
+    }
+
+    /// Same as `run_scenario`, but errors also name the file the failing step came from.
+    pub fn run_resolved_scenario(&mut self, scenario: &ResolvedScenario) -> Result<(), RunScenarioError> {
+        let check_gas = self.check_gas.or(scenario.check_gas).unwrap_or(true);
+        for (index, resolved) in scenario.steps.iter().enumerate() {
+            self.run_step_checking_gas(&resolved.step, check_gas)
+                .map_err(|error| step_error(index, Some(&resolved.file), &resolved.step, error))?;
+        }
+        Ok(())
+    }
+}
+
+fn step_error(step_index: usize, file: Option<&Path>, step: &Step, error: ExecutionError) -> RunScenarioError {
+    let tx_id = match step {
+        Step::ScCall { tx_id, .. } |
+        Step::ScDeploy { tx_id, .. } |
+        Step::Transfer { tx_id, .. } |
+        Step::ValidatorReward { tx_id, .. } => Some(tx_id.clone()),
+        _ => None,
+    };
+    RunScenarioError {
+        step_index,
+        file: file.map(Path::to_path_buf),
+        tx_id,
+        error: Box::new(error),
+    }
+}
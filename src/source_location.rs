@@ -0,0 +1,412 @@
+use super::account_raw::*;
+use super::logs_raw::*;
+use super::scenario_raw::*;
+use super::value_raw::*;
+use super::unknown_fields::*;
+use indexmap::IndexMap;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Position of a value in a scenario file. Lines and columns start at 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: Option<Arc<Path>>,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Implemented by everything that can point back to where it was written in a scenario file.
+pub trait Locatable {
+    fn location(&self) -> Option<&SourceLocation>;
+}
+
+/// Sets the location of every string value in `raw`, which was deserialized from `contents`,
+/// and the position of every unknown field among the keys of its object.
+///
+/// Serde replays internally tagged steps from a buffer and deserializes flattened fields last,
+/// so locations cannot be taken from the reader while deserializing.
+/// Instead, `contents` is scanned for the position of every JSON string by path, e.g. `steps[0].tx.from`,
+/// and the raw types are walked afterwards, looking their values up by the same paths.
+pub fn locate_values(raw: &mut ScenarioRaw, contents: &str, file: Option<&Path>) {
+    raw.locate_values("", &SourceMap::scan(contents, file));
+}
+
+/// Path of a field or map entry below `path`, e.g. `steps[0].tx` or `accounts["address:a"]`.
+/// Keys that are not plain identifiers are quoted.
+pub(crate) fn child_path(path: &str, key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    } else {
+        format!("{}[{:?}]", path, key)
+    }
+}
+
+pub(crate) fn item_path(path: &str, index: usize) -> String {
+    format!("{}[{}]", path, index)
+}
+
+struct ScannedString {
+    value: String,
+    line: usize,
+    column: usize,
+}
+
+/// An object key, with the position of its opening quote.
+pub(crate) struct ScannedKey {
+    pub name: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Where the string values of a JSON document are, the keys of each object in order
+/// and the length of each array, by path.
+pub(crate) struct SourceMap {
+    file: Option<Arc<Path>>,
+    strings: HashMap<String, ScannedString>,
+    objects: HashMap<String, Vec<ScannedKey>>,
+    arrays: HashMap<String, usize>,
+}
+
+enum Container {
+    Object { path: String, key: Option<String>, keys: Vec<ScannedKey> },
+    Array { path: String, index: usize, empty: bool },
+}
+
+/// Path of the value that starts at the current position.
+fn value_path(containers: &[Container]) -> String {
+    match containers.last() {
+        Some(Container::Object { path, key: Some(key), .. }) => child_path(path, key),
+        Some(Container::Object { path, key: None, .. }) => path.clone(),
+        Some(Container::Array { path, index, .. }) => item_path(path, *index),
+        None => String::new(),
+    }
+}
+
+impl SourceMap {
+    /// Malformed JSON is scanned as far as possible, serde reports the actual error.
+    pub(crate) fn scan(contents: &str, file: Option<&Path>) -> SourceMap {
+        let mut source = SourceMap {
+            file: file.map(Arc::from),
+            strings: HashMap::new(),
+            objects: HashMap::new(),
+            arrays: HashMap::new(),
+        };
+        let mut containers = Vec::<Container>::new();
+        let mut chars = contents.char_indices();
+        let mut line = 1;
+        let mut column = 1;
+
+        while let Some((start, c)) = chars.next() {
+            if c == '\n' {
+                line += 1;
+                column = 1;
+                continue;
+            }
+            if let Some(Container::Array { empty, .. }) = containers.last_mut() {
+                *empty &= c.is_whitespace() || c == ']';
+            }
+            if c != '"' {
+                match c {
+                    '{' => containers.push(Container::Object { path: value_path(&containers), key: None, keys: Vec::new() }),
+                    '[' => containers.push(Container::Array { path: value_path(&containers), index: 0, empty: true }),
+                    '}' | ']' => {
+                        match containers.pop() {
+                            Some(Container::Object { path, keys, .. }) => {
+                                source.objects.insert(path, keys);
+                            },
+                            Some(Container::Array { path, index, empty }) => {
+                                source.arrays.insert(path, if empty { 0 } else { index + 1 });
+                            },
+                            None => {},
+                        }
+                    },
+                    ',' => {
+                        if let Some(Container::Array { index, .. }) = containers.last_mut() {
+                            *index += 1;
+                        }
+                    },
+                    _ => {},
+                }
+                column += 1;
+                continue;
+            }
+
+            let (token_line, token_column) = (line, column);
+            column += 1;
+            let mut end = contents.len();
+            let mut escaped = false;
+            for (i, c) in chars.by_ref() {
+                column += 1;
+                if c == '\n' {
+                    line += 1;
+                    column = 1;
+                }
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    end = i + 1;
+                    break;
+                }
+            }
+
+            let Ok(value) = serde_json::from_str::<String>(&contents[start..end]) else {
+                continue;
+            };
+            let is_key = contents[end..]
+                .trim_start()
+                .starts_with(':');
+            if is_key {
+                if let Some(Container::Object { key, keys, .. }) = containers.last_mut() {
+                    keys.push(ScannedKey { name: value.clone(), line: token_line, column: token_column });
+                    *key = Some(value);
+                }
+                continue;
+            }
+            source.strings.insert(value_path(&containers), ScannedString {
+                value,
+                line: token_line,
+                column: token_column,
+            });
+        }
+
+        source
+    }
+
+    /// The string value at `path`.
+    pub(crate) fn string(&self, path: &str) -> Option<&str> {
+        self.strings.get(path).map(|scanned| scanned.value.as_str())
+    }
+
+    /// The keys of the object at `path`, in document order.
+    pub(crate) fn object_keys(&self, path: &str) -> &[ScannedKey] {
+        self.objects.get(path).map_or(&[], Vec::as_slice)
+    }
+
+    pub(crate) fn array_len(&self, path: &str) -> usize {
+        self.arrays.get(path).copied().unwrap_or(0)
+    }
+
+    /// Location of the string value at `path`, if it is the string `value`.
+    pub(crate) fn string_location(&self, path: &str, value: &str) -> Option<SourceLocation> {
+        self.strings.get(path)
+            .filter(|scanned| scanned.value == value)
+            .map(|scanned| SourceLocation {
+                file: self.file.clone(),
+                line: scanned.line,
+                column: scanned.column,
+            })
+    }
+}
+
+impl UnknownFields {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        for (position, key) in source.object_keys(path).iter().enumerate() {
+            self.set_position(&key.name, position);
+        }
+    }
+}
+
+/// Implemented by the raw types, to set the locations of their values from a `SourceMap`.
+/// `path` is the path of `self` in the document.
+pub(crate) trait LocateValues {
+    fn locate_values(&mut self, path: &str, source: &SourceMap);
+}
+
+impl<T: LocateValues> LocateValues for Option<T> {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        if let Some(item) = self {
+            item.locate_values(path, source);
+        }
+    }
+}
+
+impl<T: LocateValues> LocateValues for Vec<T> {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        for (i, item) in self.iter_mut().enumerate() {
+            item.locate_values(&item_path(path, i), source);
+        }
+    }
+}
+
+impl<T: LocateValues> LocateValues for IndexMap<String, T> {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        for (key, item) in self.iter_mut() {
+            item.locate_values(&child_path(path, key), source);
+        }
+    }
+}
+
+impl LocateValues for ValueSubTree {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        match self {
+            ValueSubTree::Str(s, location) => *location = source.string_location(path, s),
+            ValueSubTree::List(list) => list.locate_values(path, source),
+            ValueSubTree::Map(map) => map.locate_values(path, source),
+        }
+    }
+}
+
+impl LocateValues for ScenarioRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.steps.locate_values(&child_path(path, "steps"), source);
+    }
+}
+
+impl LocateValues for StepRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields_mut().locate_values(path, source);
+        match self {
+            StepRaw::ExternalSteps { .. } |
+            StepRaw::DumpState { .. } => {},
+            StepRaw::SetState { accounts, new_addresses, block_hashes, previous_block_info, current_block_info, .. } => {
+                accounts.locate_values(&child_path(path, "accounts"), source);
+                new_addresses.locate_values(&child_path(path, "newAddresses"), source);
+                block_hashes.locate_values(&child_path(path, "blockHashes"), source);
+                previous_block_info.locate_values(&child_path(path, "previousBlockInfo"), source);
+                current_block_info.locate_values(&child_path(path, "currentBlockInfo"), source);
+            },
+            StepRaw::ScCall { tx, expect, .. } => {
+                tx.locate_values(&child_path(path, "tx"), source);
+                expect.locate_values(&child_path(path, "expect"), source);
+            },
+            StepRaw::ScDeploy { tx, expect, .. } => {
+                tx.locate_values(&child_path(path, "tx"), source);
+                expect.locate_values(&child_path(path, "expect"), source);
+            },
+            StepRaw::Transfer { tx, .. } => {
+                tx.locate_values(&child_path(path, "tx"), source);
+            },
+            StepRaw::ValidatorReward { tx, .. } => {
+                tx.locate_values(&child_path(path, "tx"), source);
+            },
+            StepRaw::CheckState { accounts, .. } => {
+                accounts.accounts.locate_values(&child_path(path, "accounts"), source);
+            },
+        }
+    }
+}
+
+impl LocateValues for AccountRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.nonce.locate_values(&child_path(path, "nonce"), source);
+        self.balance.locate_values(&child_path(path, "balance"), source);
+        self.storage.locate_values(&child_path(path, "storage"), source);
+        self.code.locate_values(&child_path(path, "code"), source);
+    }
+}
+
+impl LocateValues for CheckAccountRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.nonce.locate_values(&child_path(path, "nonce"), source);
+        self.balance.locate_values(&child_path(path, "balance"), source);
+        if let CheckStorageRaw::Equal(storage) = &mut self.storage {
+            storage.locate_values(&child_path(path, "storage"), source);
+        }
+        self.code.locate_values(&child_path(path, "code"), source);
+        self.async_call_data.locate_values(&child_path(path, "asyncCallData"), source);
+    }
+}
+
+impl LocateValues for NewAddressRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.creator_address.locate_values(&child_path(path, "creatorAddress"), source);
+        self.creator_nonce.locate_values(&child_path(path, "creatorNonce"), source);
+        self.new_address.locate_values(&child_path(path, "newAddress"), source);
+    }
+}
+
+impl LocateValues for BlockInfoRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.block_timestamp.locate_values(&child_path(path, "blockTimestamp"), source);
+        self.block_nonce.locate_values(&child_path(path, "blockNonce"), source);
+        self.block_round.locate_values(&child_path(path, "blockRound"), source);
+        self.block_epoch.locate_values(&child_path(path, "blockEpoch"), source);
+    }
+}
+
+impl LocateValues for TxCallRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.from.locate_values(&child_path(path, "from"), source);
+        self.to.locate_values(&child_path(path, "to"), source);
+        self.value.locate_values(&child_path(path, "value"), source);
+        self.arguments.locate_values(&child_path(path, "arguments"), source);
+        self.gas_limit.locate_values(&child_path(path, "gasLimit"), source);
+        self.gas_price.locate_values(&child_path(path, "gasPrice"), source);
+    }
+}
+
+impl LocateValues for TxDeployRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.from.locate_values(&child_path(path, "from"), source);
+        self.value.locate_values(&child_path(path, "value"), source);
+        self.contract_code.locate_values(&child_path(path, "contractCode"), source);
+        self.arguments.locate_values(&child_path(path, "arguments"), source);
+        self.gas_limit.locate_values(&child_path(path, "gasLimit"), source);
+        self.gas_price.locate_values(&child_path(path, "gasPrice"), source);
+    }
+}
+
+impl LocateValues for TxTransferRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.from.locate_values(&child_path(path, "from"), source);
+        self.to.locate_values(&child_path(path, "to"), source);
+        self.value.locate_values(&child_path(path, "value"), source);
+    }
+}
+
+impl LocateValues for TxValidatorRewardRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.to.locate_values(&child_path(path, "to"), source);
+        self.value.locate_values(&child_path(path, "value"), source);
+    }
+}
+
+impl LocateValues for TxExpectRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.out.locate_values(&child_path(path, "out"), source);
+        self.status.locate_values(&child_path(path, "status"), source);
+        if let CheckLogsRaw::List(logs) = &mut self.logs {
+            logs.locate_values(&child_path(path, "logs"), source);
+        }
+        self.message.locate_values(&child_path(path, "message"), source);
+        self.gas.locate_values(&child_path(path, "gas"), source);
+        self.refund.locate_values(&child_path(path, "refund"), source);
+    }
+}
+
+impl LocateValues for CheckLogRaw {
+    fn locate_values(&mut self, path: &str, source: &SourceMap) {
+        self.unknown_fields.locate_values(path, source);
+        self.address.locate_values(&child_path(path, "address"), source);
+        self.identifier.locate_values(&child_path(path, "identifier"), source);
+        self.topics.locate_values(&child_path(path, "topics"), source);
+        self.data.locate_values(&child_path(path, "data"), source);
+    }
+}
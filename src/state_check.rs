@@ -0,0 +1,247 @@
+use super::account::*;
+use super::error::*;
+use super::value::*;
+use super::value_check::*;
+use super::value_formatter::*;
+use super::value_raw::*;
+use super::world_state::*;
+use indexmap::IndexMap;
+use std::fmt;
+
+/// One difference between a `checkState` step and the actual world state.
+/// Addresses and keys are given as written in the scenario, or formatted if the scenario does not mention them.
+/// Expected values are the scenario originals, actual values are formatted as Mandos expressions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateMismatch {
+    MissingAccount {
+        address: String,
+    },
+    UnexpectedAccount {
+        address: String,
+    },
+    Nonce {
+        address: String,
+        expected: String,
+        actual: String,
+    },
+    Balance {
+        address: String,
+        expected: String,
+        actual: String,
+    },
+    Code {
+        address: String,
+        expected: String,
+        actual: String,
+    },
+    AsyncCallData {
+        address: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// The scenario expects a value for a key that is not in storage.
+    MissingStorageKey {
+        address: String,
+        key: String,
+        expected: String,
+    },
+
+    /// The key is in storage, but not in the scenario.
+    UnexpectedStorageKey {
+        address: String,
+        key: String,
+        actual: String,
+    },
+    StorageValue {
+        address: String,
+        key: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for StateMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateMismatch::MissingAccount { address } =>
+                write!(f, "account {} expected, but not found", address),
+            StateMismatch::UnexpectedAccount { address } =>
+                write!(f, "unexpected account {}", address),
+            StateMismatch::Nonce { address, expected, actual } =>
+                write!(f, "bad nonce for account {}: expected {:?}, actual {:?}", address, expected, actual),
+            StateMismatch::Balance { address, expected, actual } =>
+                write!(f, "bad balance for account {}: expected {:?}, actual {:?}", address, expected, actual),
+            StateMismatch::Code { address, expected, actual } =>
+                write!(f, "bad code for account {}: expected {:?}, actual {:?}", address, expected, actual),
+            StateMismatch::AsyncCallData { address, expected, actual } =>
+                write!(f, "bad async call data for account {}: expected {:?}, actual {:?}", address, expected, actual),
+            StateMismatch::MissingStorageKey { address, key, expected } =>
+                write!(f, "storage key {} of account {} expected to be {:?}, but not found", key, address, expected),
+            StateMismatch::UnexpectedStorageKey { address, key, actual } =>
+                write!(f, "unexpected storage key {} of account {}, with value {:?}", key, address, actual),
+            StateMismatch::StorageValue { address, key, expected, actual } =>
+                write!(f, "bad storage value for key {} of account {}: expected {:?}, actual {:?}", key, address, expected, actual),
+        }
+    }
+}
+
+impl CheckAccounts {
+    /// Compares the world state against the expected accounts, collecting every difference.
+    /// Fails with `MandosError::FileNotLoaded` if an expected value is a lazily loaded file,
+    /// since there is nothing to compare against.
+    pub fn mismatches(&self, world: &WorldState) -> Result<Vec<StateMismatch>, MandosError> {
+        let mut mismatches = Vec::new();
+        for (address, check_account) in self.accounts.iter() {
+            match world.account(&address.value) {
+                Some(account) => check_account.collect_mismatches(&address.original, account, &mut mismatches)?,
+                None => mismatches.push(StateMismatch::MissingAccount {
+                    address: address.original.clone(),
+                }),
+            }
+        }
+        if !self.other_accounts_allowed {
+            for address in world.accounts.keys() {
+                let expected = self.accounts.keys().any(|key| &key.value == address);
+                if !expected {
+                    mismatches.push(StateMismatch::UnexpectedAccount {
+                        address: format_value(address, FormatHint::Address),
+                    });
+                }
+            }
+        }
+        Ok(mismatches)
+    }
+
+    /// `Ok` if the world state passes the check, otherwise an `ExecutionError::StateMismatch`
+    /// with every account and field that differs.
+    pub fn check(&self, world: &WorldState) -> Result<(), ExecutionError> {
+        let mismatches = self.mismatches(world).map_err(ExecutionError::Interpret)?;
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(ExecutionError::StateMismatch { mismatches })
+        }
+    }
+}
+
+impl CheckAccount {
+    /// Fails if an expected value is a lazily loaded file, as `CheckAccounts::mismatches` does.
+    pub fn mismatches(&self, address: &str, account: &AccountState) -> Result<Vec<StateMismatch>, MandosError> {
+        let mut mismatches = Vec::new();
+        self.collect_mismatches(address, account, &mut mismatches)?;
+        Ok(mismatches)
+    }
+
+    fn collect_mismatches(
+        &self,
+        address: &str,
+        account: &AccountState,
+        mismatches: &mut Vec<StateMismatch>,
+    ) -> Result<(), MandosError> {
+        self.require_loaded()?;
+        let address = address.to_string();
+        if !self.nonce.check(account.nonce) {
+            mismatches.push(StateMismatch::Nonce {
+                address: address.clone(),
+                expected: expected_text(&self.nonce),
+                actual: account.nonce.to_string(),
+            });
+        }
+        if !self.balance.check(&account.balance) {
+            mismatches.push(StateMismatch::Balance {
+                address: address.clone(),
+                expected: expected_text(&self.balance),
+                actual: account.balance.to_string(),
+            });
+        }
+        if let Some(code) = &self.code {
+            let actual = account.code.as_deref().unwrap_or_default();
+            if !code.check(actual) {
+                mismatches.push(StateMismatch::Code {
+                    address: address.clone(),
+                    expected: expected_text(code),
+                    actual: format_value(actual, FormatHint::Auto),
+                });
+            }
+        }
+        if !self.async_call_data.check(account.async_call_data.as_slice()) {
+            mismatches.push(StateMismatch::AsyncCallData {
+                address: address.clone(),
+                expected: expected_text(&self.async_call_data),
+                actual: format_value(&account.async_call_data, FormatHint::Auto),
+            });
+        }
+        if let CheckStorage::Equal(expected_storage) = &self.storage {
+            collect_storage_mismatches(&address, expected_storage, account, mismatches);
+        }
+        Ok(())
+    }
+
+    /// The bytes values of the check, other than stars, must not be lazily loaded files.
+    fn require_loaded(&self) -> Result<(), MandosError> {
+        let storage_values = match &self.storage {
+            CheckStorage::Equal(storage) => storage.values().collect(),
+            CheckStorage::Star => Vec::new(),
+        };
+        for check_value in self.code.iter().chain([&self.async_call_data]).chain(storage_values) {
+            if let CheckValue::Equal(value) = check_value {
+                value.loaded()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// In storage checks, an empty expected value means that the key must be absent,
+/// rather than that anything goes.
+fn collect_storage_mismatches(
+    address: &str,
+    expected_storage: &IndexMap<BytesKey, CheckValue<BytesValue>>,
+    account: &AccountState,
+    mismatches: &mut Vec<StateMismatch>,
+) {
+    for (key, expected) in expected_storage.iter() {
+        let actual = account.storage_value(&key.value);
+        let matches = match expected {
+            CheckValue::DefaultStar => actual.is_empty(),
+            _ => expected.check(actual),
+        };
+        if matches {
+            continue;
+        }
+        if actual.is_empty() {
+            mismatches.push(StateMismatch::MissingStorageKey {
+                address: address.to_string(),
+                key: key.original.clone(),
+                expected: expected_text(expected),
+            });
+        } else {
+            mismatches.push(StateMismatch::StorageValue {
+                address: address.to_string(),
+                key: key.original.clone(),
+                expected: expected_text(expected),
+                actual: format_value(actual, FormatHint::Auto),
+            });
+        }
+    }
+    for (key, value) in account.storage.iter() {
+        let expected = expected_storage.keys().any(|expected_key| &expected_key.value == key);
+        if !expected {
+            mismatches.push(StateMismatch::UnexpectedStorageKey {
+                address: address.to_string(),
+                key: format_value(key, FormatHint::Auto),
+                actual: format_value(value, FormatHint::Auto),
+            });
+        }
+    }
+}
+
+/// Expected values are reported when they are `Equal`, which writes back the original,
+/// or `DefaultStar` in storage, which writes back `""`: the key must be absent.
+fn expected_text<T: IntoRaw<ValueSubTree> + Clone>(check_value: &CheckValue<T>) -> String {
+    match check_value.clone().into_raw() {
+        ValueSubTree::Str(s, _) => s,
+        other => other.to_string(),
+    }
+}
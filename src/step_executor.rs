@@ -0,0 +1,58 @@
+use super::error::*;
+use super::scenario::*;
+use super::world_state::*;
+use num_bigint::BigUint;
+
+impl WorldState {
+    /// Executes the steps that need no contract VM.
+    /// `checkState` reports every difference, `dumpState` does nothing,
+    /// every other step is reported as unsupported.
+    pub fn execute_step(&mut self, step: &Step) -> Result<(), ExecutionError> {
+        match step {
+            Step::SetState { .. } => {
+                self.apply_set_state(step).map_err(ExecutionError::Interpret)?;
+                Ok(())
+            },
+            Step::Transfer { tx, .. } => self.transfer(tx),
+            Step::ValidatorReward { tx, .. } => self.validator_reward(tx),
+            Step::CheckState { accounts, .. } => accounts.check(self),
+            Step::DumpState { .. } => Ok(()),
+            Step::ExternalSteps { .. } => Err(ExecutionError::UnsupportedStep { step: "externalSteps" }),
+            Step::ScCall { .. } => Err(ExecutionError::UnsupportedStep { step: "scCall" }),
+            Step::ScDeploy { .. } => Err(ExecutionError::UnsupportedStep { step: "scDeploy" }),
+        }
+    }
+
+    /// Moves `value` from sender to receiver and increments the sender's nonce.
+    /// Both accounts must exist. Nothing changes if the transfer fails.
+    pub fn transfer(&mut self, tx: &TxTransfer) -> Result<(), ExecutionError> {
+        let (from, to) = (tx.from.value, tx.to.value);
+        if self.account(&to).is_none() {
+            return Err(ExecutionError::UnknownAccount { address: to });
+        }
+        let sender = self.account_mut(&from)
+            .ok_or(ExecutionError::UnknownAccount { address: from })?;
+        if sender.balance < tx.value.value {
+            return Err(ExecutionError::InsufficientFunds {
+                address: from,
+                balance: sender.balance.clone(),
+                value: tx.value.value.clone(),
+            });
+        }
+        sender.balance -= &tx.value.value;
+        sender.nonce += 1;
+        self.credit(&to, &tx.value.value)
+    }
+
+    /// Credits the receiver, which must exist.
+    pub fn validator_reward(&mut self, tx: &TxValidatorReward) -> Result<(), ExecutionError> {
+        self.credit(&tx.to.value, &tx.value.value)
+    }
+
+    fn credit(&mut self, address: &[u8; 32], value: &BigUint) -> Result<(), ExecutionError> {
+        let account = self.account_mut(address)
+            .ok_or(ExecutionError::UnknownAccount { address: *address })?;
+        account.balance += value;
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use super::contract_executor::*;
+use super::error::*;
+use super::logs::*;
+use super::logs_raw::*;
+use super::scenario::*;
+use super::unknown_fields::*;
+use super::value::*;
+use super::value_check::*;
+use super::value_formatter::*;
+use super::value_raw::*;
+use indexmap::IndexMap;
+use std::fmt;
+
+/// One field of a transaction result that does not match its `expect`.
+/// Expected values are the scenario originals, actual values are formatted as Mandos expressions.
+/// Lists (`out` and `logs`) are compared and reported as a whole, as JSON lists of such expressions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TxMismatch {
+    Out {
+        expected: String,
+        actual: String,
+    },
+    Status {
+        expected: String,
+        actual: String,
+    },
+    Message {
+        expected: String,
+        actual: String,
+    },
+    Logs {
+        expected: String,
+        actual: String,
+    },
+    Gas {
+        expected: String,
+        actual: String,
+    },
+    Refund {
+        expected: String,
+        actual: String,
+    },
+}
+
+impl fmt::Display for TxMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxMismatch::Out { expected, actual } =>
+                write!(f, "bad out: expected {}, actual {}", expected, actual),
+            TxMismatch::Status { expected, actual } =>
+                write!(f, "bad status: expected {:?}, actual {:?}", expected, actual),
+            TxMismatch::Message { expected, actual } =>
+                write!(f, "bad message: expected {:?}, actual {:?}", expected, actual),
+            TxMismatch::Logs { expected, actual } =>
+                write!(f, "bad logs: expected {}, actual {}", expected, actual),
+            TxMismatch::Gas { expected, actual } =>
+                write!(f, "bad gas: expected {:?}, actual {:?}", expected, actual),
+            TxMismatch::Refund { expected, actual } =>
+                write!(f, "bad refund: expected {:?}, actual {:?}", expected, actual),
+        }
+    }
+}
+
+impl TxExpect {
+    /// Compares the result against every field of the expectation, collecting every difference.
+    /// Gas is only compared if `check_gas` is set. Whether gas is checked is decided by the whole scenario
+    /// (see `Scenario::check_gas`) or by the runner, which an expectation on its own does not know about.
+    pub fn mismatches(&self, result: &TxResult, check_gas: bool) -> Vec<TxMismatch> {
+        let mut mismatches = Vec::new();
+        if !self.out.check(result.out.as_slice()) {
+            mismatches.push(TxMismatch::Out {
+                expected: ValueSubTree::List(self.out.iter().cloned().map(IntoRaw::into_raw).collect()).to_string(),
+                actual: ValueSubTree::List(result.out.iter().map(|out| formatted(out)).collect()).to_string(),
+            });
+        }
+        if !self.status.check(result.status) {
+            mismatches.push(TxMismatch::Status {
+                expected: plain_text(self.status.original.clone()),
+                actual: result.status.to_string(),
+            });
+        }
+        if let Some(message) = &self.message {
+            if !message.check(result.message.as_slice()) {
+                mismatches.push(TxMismatch::Message {
+                    expected: plain_text(message.original.clone()),
+                    actual: format_value(&result.message, FormatHint::Auto),
+                });
+            }
+        }
+        if !self.logs.check(result.logs.as_slice()) {
+            mismatches.push(TxMismatch::Logs {
+                expected: logs_text(self.logs.clone().into_raw()),
+                actual: logs_text(actual_logs_raw(&result.logs)),
+            });
+        }
+        if let Some(gas) = &self.gas {
+            if check_gas && !gas.check(result.gas) {
+                mismatches.push(TxMismatch::Gas {
+                    expected: plain_text(gas.clone().into_raw()),
+                    actual: result.gas.to_string(),
+                });
+            }
+        }
+        if let Some(refund) = &self.refund {
+            if !refund.check(result.refund) {
+                mismatches.push(TxMismatch::Refund {
+                    expected: plain_text(refund.clone().into_raw()),
+                    actual: result.refund.to_string(),
+                });
+            }
+        }
+        mismatches
+    }
+
+    /// `Ok` if the result meets the expectation, otherwise an `ExecutionError::TxMismatch` with every field that differs.
+    /// Gas is only compared if `check_gas` is set.
+    pub fn check(&self, result: &TxResult, check_gas: bool) -> Result<(), ExecutionError> {
+        let mismatches = self.mismatches(result, check_gas);
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(ExecutionError::TxMismatch { mismatches })
+        }
+    }
+}
+
+/// `Star` and `DefaultStar` accept any logs.
+impl Checkable<&[TxLog]> for CheckLogs {
+    fn check(&self, logs: &[TxLog]) -> bool {
+        match self {
+            CheckLogs::Star | CheckLogs::DefaultStar => true,
+            CheckLogs::List(expected) =>
+                expected.len() == logs.len() &&
+                expected.iter().zip(logs.iter()).all(|(check_log, log)| check_log.check(log)),
+        }
+    }
+}
+
+impl Checkable<&TxLog> for CheckLog {
+    fn check(&self, log: &TxLog) -> bool {
+        self.address.check(&log.address[..]) &&
+            self.identifier.check(log.identifier.as_slice()) &&
+            self.topics.len() == log.topics.len() &&
+            self.topics.iter().zip(log.topics.iter()).all(|(expected, topic)| expected.check(topic.as_slice())) &&
+            self.data.check(log.data.as_slice())
+    }
+}
+
+/// The expression itself when the value is a plain string, which all scalar values are.
+fn plain_text(value: ValueSubTree) -> String {
+    match value {
+        ValueSubTree::Str(s, _) => s,
+        other => other.to_string(),
+    }
+}
+
+/// Only a list of logs can be a mismatch, `*` accepts anything.
+fn logs_text(logs: CheckLogsRaw) -> String {
+    match logs {
+        CheckLogsRaw::List(logs) => ValueSubTree::List(logs.into_iter().map(log_subtree).collect()).to_string(),
+        CheckLogsRaw::Star | CheckLogsRaw::DefaultStar => "*".to_string(),
+    }
+}
+
+fn log_subtree(log: CheckLogRaw) -> ValueSubTree {
+    let mut fields = IndexMap::new();
+    fields.insert("address".to_string(), log.address);
+    fields.insert("identifier".to_string(), log.identifier);
+    if !log.topics.is_empty() {
+        fields.insert("topics".to_string(), ValueSubTree::List(log.topics));
+    }
+    fields.insert("data".to_string(), log.data);
+    ValueSubTree::Map(fields)
+}
+
+fn formatted(bytes: &[u8]) -> ValueSubTree {
+    ValueSubTree::from(format_value(bytes, FormatHint::Auto))
+}
+
+/// The logs, written the way a scenario would expect them.
+fn actual_logs_raw(logs: &[TxLog]) -> CheckLogsRaw {
+    CheckLogsRaw::List(logs.iter().map(|log| CheckLogRaw {
+        address: ValueSubTree::from(format_value(&log.address, FormatHint::Address)),
+        identifier: formatted(&log.identifier),
+        topics: log.topics.iter().map(|topic| formatted(topic)).collect(),
+        data: formatted(&log.data),
+        unknown_fields: UnknownFields::new(),
+    }).collect())
+}
@@ -0,0 +1,751 @@
+use super::account_raw::*;
+use super::logs_raw::*;
+use super::scenario_raw::*;
+use super::value_raw::*;
+use super::source_location::*;
+use super::error::*;
+use indexmap::IndexMap;
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// JSON fields that are not part of the Mandos format.
+/// They are kept so that a parse/serialize round-trip does not lose them.
+///
+/// Serde writes flattened fields after all the others, so each field also remembers
+/// its position among the keys of its object when it was parsed from a file,
+/// and `scenario_to_string` moves it back there.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UnknownFields {
+    fields: IndexMap<String, UnknownValue>,
+    positions: HashMap<String, usize>,
+}
+
+impl UnknownFields {
+    pub fn new() -> Self {
+        UnknownFields::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&UnknownValue> {
+        self.fields.get(key)
+    }
+
+    /// Fields inserted here have no position, they are written after the known fields.
+    pub fn insert(&mut self, key: String, value: UnknownValue) -> Option<UnknownValue> {
+        self.positions.remove(&key);
+        self.fields.insert(key, value)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<UnknownValue> {
+        self.positions.remove(key);
+        self.fields.shift_remove(key)
+    }
+
+    pub fn iter(&self) -> indexmap::map::Iter<'_, String, UnknownValue> {
+        self.fields.iter()
+    }
+
+    pub fn keys(&self) -> indexmap::map::Keys<'_, String, UnknownValue> {
+        self.fields.keys()
+    }
+
+    /// Index of `key` among all the keys of its object, in the file it was parsed from.
+    pub fn position(&self, key: &str) -> Option<usize> {
+        self.positions.get(key).copied()
+    }
+
+    pub(crate) fn set_position(&mut self, key: &str, position: usize) {
+        if self.fields.contains_key(key) {
+            self.positions.insert(key.to_string(), position);
+        }
+    }
+}
+
+impl Serialize for UnknownFields {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.fields.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for UnknownFields {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = IndexMap::deserialize(deserializer)?;
+        Ok(UnknownFields { fields, positions: HashMap::new() })
+    }
+}
+
+/// The JSON value of an unknown field. Unlike `serde_json::Value`, objects keep their keys in file order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UnknownValue {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    List(Vec<UnknownValue>),
+    Map(IndexMap<String, UnknownValue>),
+}
+
+/// What to do with JSON fields that are not part of the Mandos format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownFieldPolicy {
+    /// Keep them in the `unknown_fields` of the enclosing raw struct.
+    #[default]
+    Keep,
+
+    /// Strict mode: fail the parse with `ParseScenarioError::UnknownField`,
+    /// suggesting the closest valid field name.
+    Reject,
+}
+
+/// A key in a scenario file that is not a field of the Mandos format,
+/// with the closest valid field name if it looks like a typo.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct UnknownField {
+    field: String,
+    line: usize,
+    column: usize,
+    suggestion: Option<String>,
+}
+
+/// The path of an object in a scenario, with its unknown fields.
+type ObjectUnknownFields<'a> = (String, &'a UnknownFields);
+
+impl ScenarioRaw {
+    /// Paths of all unknown fields in the scenario, e.g. `steps[2].tx.gasLimt`.
+    /// The unknown fields of an object are listed before those of its children.
+    pub fn unknown_field_paths(&self) -> Vec<String> {
+        self.objects_with_unknown_fields()
+            .into_iter()
+            .flat_map(|(path, fields)| fields.keys().map(move |key| child_path(&path, key)))
+            .collect()
+    }
+
+    fn objects_with_unknown_fields(&self) -> Vec<ObjectUnknownFields<'_>> {
+        let mut found = Vec::new();
+        self.collect_unknown_fields("", &mut found);
+        found
+    }
+
+    /// The scenario as a JSON value, with each unknown field at the position it had in the file it was parsed from.
+    pub(crate) fn to_ordered_value(&self) -> UnknownValue {
+        let serialized = serde_json::to_string(self).expect("scenario serialization cannot fail");
+        let mut value = serde_json::from_str(&serialized).expect("a serialized scenario is valid JSON");
+        let objects: HashMap<String, &UnknownFields> = self.objects_with_unknown_fields().into_iter().collect();
+        if !objects.is_empty() {
+            restore_positions(&mut value, "", &objects);
+        }
+        value
+    }
+}
+
+fn restore_positions(value: &mut UnknownValue, path: &str, objects: &HashMap<String, &UnknownFields>) {
+    match value {
+        UnknownValue::List(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                restore_positions(item, &item_path(path, i), objects);
+            }
+        },
+        UnknownValue::Map(entries) => {
+            if let Some(unknown_fields) = objects.get(path) {
+                let mut positioned: Vec<(usize, &String)> = unknown_fields
+                    .keys()
+                    .filter_map(|key| Some((unknown_fields.position(key)?, key)))
+                    .collect();
+                positioned.sort();
+                let moved: Vec<(usize, String, UnknownValue)> = positioned
+                    .into_iter()
+                    .filter_map(|(position, key)| Some((position, key.clone(), entries.shift_remove(key)?)))
+                    .collect();
+                for (position, key, item) in moved {
+                    entries.shift_insert(position.min(entries.len()), key, item);
+                }
+            }
+            for (key, item) in entries.iter_mut() {
+                restore_positions(item, &child_path(path, key), objects);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// Strict mode, applied to the result of deserializing the file scanned into `source`.
+/// The keys of the file are checked against the fields of the raw type found at their path,
+/// rather than the unknown fields of the result, so that a misspelled required field is reported
+/// as unknown even when serde gave up on it as missing.
+pub(crate) fn reject_unknown_fields(
+    path: &Path,
+    source: &SourceMap,
+    parsed: Result<ScenarioRaw, ParseScenarioError>,
+) -> Result<ScenarioRaw, ParseScenarioError> {
+    if matches!(parsed, Ok(_) | Err(ParseScenarioError::Schema { .. })) {
+        let mut found = Vec::new();
+        ScenarioRaw::find_unknown_keys("", source, &mut found);
+        if let Some(UnknownField { field, line, column, suggestion }) =
+            found.into_iter().min_by_key(|unknown| (unknown.line, unknown.column))
+        {
+            return Err(ParseScenarioError::UnknownField { path: path.to_path_buf(), line, column, field, suggestion });
+        }
+    }
+    parsed
+}
+
+/// Implemented by the raw structs, to find out which field names serde knows for them.
+trait WithEveryField: Serialize + Sized {
+    /// A value with every optional field set, so that none of them is skipped when serializing it.
+    fn with_every_field() -> Self;
+}
+
+/// The field names serde writes for `item`.
+fn field_names<T: Serialize>(item: &T) -> Vec<String> {
+    match serde_json::to_value(item) {
+        Ok(serde_json::Value::Object(fields)) => fields.into_iter().map(|(key, _)| key).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Implemented by the raw types, to find the keys of a scanned file that are not among their fields.
+/// `path` is the path of a value of the type in the file.
+trait FindUnknownKeys {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>);
+}
+
+fn find_own(known_fields: &[String], path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+    for key in source.object_keys(path) {
+        if !known_fields.contains(&key.name) {
+            found.push(UnknownField {
+                field: child_path(path, &key.name),
+                line: key.line,
+                column: key.column,
+                suggestion: closest_field(&key.name, known_fields),
+            });
+        }
+    }
+}
+
+fn find_in_list<T: FindUnknownKeys>(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+    for i in 0..source.array_len(path) {
+        T::find_unknown_keys(&item_path(path, i), source, found);
+    }
+}
+
+fn find_in_map<T: FindUnknownKeys>(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+    for key in source.object_keys(path) {
+        T::find_unknown_keys(&child_path(path, &key.name), source, found);
+    }
+}
+
+impl FindUnknownKeys for ScenarioRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+        find_in_list::<StepRaw>(&child_path(path, "steps"), source, found);
+    }
+}
+
+impl FindUnknownKeys for StepRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        // Serde reports missing and unknown step types.
+        let Some(step) = source.string(&child_path(path, "step")).and_then(StepRaw::with_every_field) else {
+            return;
+        };
+        find_own(&field_names(&step), path, source, found);
+        match step {
+            StepRaw::ExternalSteps { .. } |
+            StepRaw::DumpState { .. } => {},
+            StepRaw::SetState { .. } => {
+                find_in_map::<AccountRaw>(&child_path(path, "accounts"), source, found);
+                find_in_list::<NewAddressRaw>(&child_path(path, "newAddresses"), source, found);
+                BlockInfoRaw::find_unknown_keys(&child_path(path, "previousBlockInfo"), source, found);
+                BlockInfoRaw::find_unknown_keys(&child_path(path, "currentBlockInfo"), source, found);
+            },
+            StepRaw::ScCall { .. } => {
+                TxCallRaw::find_unknown_keys(&child_path(path, "tx"), source, found);
+                TxExpectRaw::find_unknown_keys(&child_path(path, "expect"), source, found);
+            },
+            StepRaw::ScDeploy { .. } => {
+                TxDeployRaw::find_unknown_keys(&child_path(path, "tx"), source, found);
+                TxExpectRaw::find_unknown_keys(&child_path(path, "expect"), source, found);
+            },
+            StepRaw::Transfer { .. } => {
+                TxTransferRaw::find_unknown_keys(&child_path(path, "tx"), source, found);
+            },
+            StepRaw::ValidatorReward { .. } => {
+                TxValidatorRewardRaw::find_unknown_keys(&child_path(path, "tx"), source, found);
+            },
+            StepRaw::CheckState { .. } => {
+                find_in_map::<CheckAccountRaw>(&child_path(path, "accounts"), source, found);
+            },
+        }
+    }
+}
+
+impl FindUnknownKeys for AccountRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for CheckAccountRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for NewAddressRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for BlockInfoRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for TxCallRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for TxDeployRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for TxTransferRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for TxValidatorRewardRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+impl FindUnknownKeys for TxExpectRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+        find_in_list::<CheckLogRaw>(&child_path(path, "logs"), source, found);
+    }
+}
+
+impl FindUnknownKeys for CheckLogRaw {
+    fn find_unknown_keys(path: &str, source: &SourceMap, found: &mut Vec<UnknownField>) {
+        find_own(&field_names(&Self::with_every_field()), path, source, found);
+    }
+}
+
+trait CollectUnknownFields {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>);
+}
+
+fn collect_own<'a>(unknown_fields: &'a UnknownFields, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+    if !unknown_fields.is_empty() {
+        found.push((path.to_string(), unknown_fields));
+    }
+}
+
+fn collect_list<'a, T: CollectUnknownFields>(items: &'a [T], path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+    for (i, item) in items.iter().enumerate() {
+        item.collect_unknown_fields(&item_path(path, i), found);
+    }
+}
+
+fn collect_map<'a, T: CollectUnknownFields>(items: &'a IndexMap<String, T>, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+    for (key, item) in items.iter() {
+        item.collect_unknown_fields(&child_path(path, key), found);
+    }
+}
+
+impl<T: CollectUnknownFields> CollectUnknownFields for Option<T> {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        if let Some(item) = self {
+            item.collect_unknown_fields(path, found);
+        }
+    }
+}
+
+impl CollectUnknownFields for ScenarioRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+        collect_list(&self.steps, &child_path(path, "steps"), found);
+    }
+}
+
+impl CollectUnknownFields for StepRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(self.unknown_fields(), path, found);
+        match self {
+            StepRaw::ExternalSteps { .. } |
+            StepRaw::DumpState { .. } => {},
+            StepRaw::SetState { accounts, new_addresses, previous_block_info, current_block_info, .. } => {
+                collect_map(accounts, &child_path(path, "accounts"), found);
+                collect_list(new_addresses, &child_path(path, "newAddresses"), found);
+                previous_block_info.collect_unknown_fields(&child_path(path, "previousBlockInfo"), found);
+                current_block_info.collect_unknown_fields(&child_path(path, "currentBlockInfo"), found);
+            },
+            StepRaw::ScCall { tx, expect, .. } => {
+                tx.collect_unknown_fields(&child_path(path, "tx"), found);
+                expect.collect_unknown_fields(&child_path(path, "expect"), found);
+            },
+            StepRaw::ScDeploy { tx, expect, .. } => {
+                tx.collect_unknown_fields(&child_path(path, "tx"), found);
+                expect.collect_unknown_fields(&child_path(path, "expect"), found);
+            },
+            StepRaw::Transfer { tx, .. } => {
+                tx.collect_unknown_fields(&child_path(path, "tx"), found);
+            },
+            StepRaw::ValidatorReward { tx, .. } => {
+                tx.collect_unknown_fields(&child_path(path, "tx"), found);
+            },
+            StepRaw::CheckState { accounts, .. } => {
+                collect_map(&accounts.accounts, &child_path(path, "accounts"), found);
+            },
+        }
+    }
+}
+
+impl CollectUnknownFields for AccountRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for CheckAccountRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for NewAddressRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for BlockInfoRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for TxCallRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for TxDeployRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for TxTransferRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for TxValidatorRewardRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl CollectUnknownFields for TxExpectRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+        if let CheckLogsRaw::List(logs) = &self.logs {
+            collect_list(logs, &child_path(path, "logs"), found);
+        }
+    }
+}
+
+impl CollectUnknownFields for CheckLogRaw {
+    fn collect_unknown_fields<'a>(&'a self, path: &str, found: &mut Vec<ObjectUnknownFields<'a>>) {
+        collect_own(&self.unknown_fields, path, found);
+    }
+}
+
+impl StepRaw {
+    fn unknown_fields(&self) -> &UnknownFields {
+        match self {
+            StepRaw::ExternalSteps { unknown_fields, .. } |
+            StepRaw::SetState { unknown_fields, .. } |
+            StepRaw::ScCall { unknown_fields, .. } |
+            StepRaw::ScDeploy { unknown_fields, .. } |
+            StepRaw::Transfer { unknown_fields, .. } |
+            StepRaw::ValidatorReward { unknown_fields, .. } |
+            StepRaw::CheckState { unknown_fields, .. } |
+            StepRaw::DumpState { unknown_fields, .. } => unknown_fields,
+        }
+    }
+
+    pub(crate) fn unknown_fields_mut(&mut self) -> &mut UnknownFields {
+        match self {
+            StepRaw::ExternalSteps { unknown_fields, .. } |
+            StepRaw::SetState { unknown_fields, .. } |
+            StepRaw::ScCall { unknown_fields, .. } |
+            StepRaw::ScDeploy { unknown_fields, .. } |
+            StepRaw::Transfer { unknown_fields, .. } |
+            StepRaw::ValidatorReward { unknown_fields, .. } |
+            StepRaw::CheckState { unknown_fields, .. } |
+            StepRaw::DumpState { unknown_fields, .. } => unknown_fields,
+        }
+    }
+
+    /// A step with the `step` tag `tag` and every optional field set.
+    fn with_every_field(tag: &str) -> Option<StepRaw> {
+        StepRaw::every_step_type()
+            .into_iter()
+            .find(|step| matches!(serde_json::to_value(step), Ok(value) if value["step"] == tag))
+    }
+
+    fn every_step_type() -> Vec<StepRaw> {
+        let comment = || Some(String::new());
+        vec![
+            StepRaw::ExternalSteps {
+                path: String::new(),
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::SetState {
+                comment: comment(),
+                accounts: IndexMap::from([(String::new(), AccountRaw::with_every_field())]),
+                new_addresses: vec![NewAddressRaw::with_every_field()],
+                block_hashes: vec![some_value()],
+                previous_block_info: Some(BlockInfoRaw::with_every_field()),
+                current_block_info: Some(BlockInfoRaw::with_every_field()),
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::ScCall {
+                tx_id: String::new(),
+                comment: comment(),
+                tx: TxCallRaw::with_every_field(),
+                expect: Some(TxExpectRaw::with_every_field()),
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::ScDeploy {
+                tx_id: String::new(),
+                comment: comment(),
+                tx: TxDeployRaw::with_every_field(),
+                expect: Some(TxExpectRaw::with_every_field()),
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::Transfer {
+                tx_id: String::new(),
+                comment: comment(),
+                tx: TxTransferRaw::with_every_field(),
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::ValidatorReward {
+                tx_id: String::new(),
+                comment: comment(),
+                tx: TxValidatorRewardRaw::with_every_field(),
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::CheckState {
+                comment: comment(),
+                accounts: CheckAccountsRaw {
+                    other_accounts_allowed: false,
+                    accounts: IndexMap::new(),
+                },
+                unknown_fields: UnknownFields::new(),
+            },
+            StepRaw::DumpState {
+                comment: comment(),
+                unknown_fields: UnknownFields::new(),
+            },
+        ]
+    }
+}
+
+fn some_value() -> ValueSubTree {
+    ValueSubTree::Str("0".to_string(), None)
+}
+
+impl WithEveryField for ScenarioRaw {
+    fn with_every_field() -> Self {
+        ScenarioRaw {
+            name: Some(String::new()),
+            comment: Some(String::new()),
+            check_gas: Some(false),
+            steps: Vec::new(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for AccountRaw {
+    fn with_every_field() -> Self {
+        AccountRaw {
+            comment: Some(String::new()),
+            nonce: some_value(),
+            balance: some_value(),
+            storage: IndexMap::new(),
+            code: Some(some_value()),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for CheckAccountRaw {
+    fn with_every_field() -> Self {
+        CheckAccountRaw {
+            comment: Some(String::new()),
+            nonce: some_value(),
+            balance: some_value(),
+            storage: CheckStorageRaw::Star,
+            code: Some(some_value()),
+            async_call_data: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for NewAddressRaw {
+    fn with_every_field() -> Self {
+        NewAddressRaw {
+            creator_address: some_value(),
+            creator_nonce: some_value(),
+            new_address: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for BlockInfoRaw {
+    fn with_every_field() -> Self {
+        BlockInfoRaw {
+            block_timestamp: Some(some_value()),
+            block_nonce: Some(some_value()),
+            block_round: Some(some_value()),
+            block_epoch: Some(some_value()),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for TxCallRaw {
+    fn with_every_field() -> Self {
+        TxCallRaw {
+            from: some_value(),
+            to: some_value(),
+            value: some_value(),
+            function: String::new(),
+            arguments: Vec::new(),
+            gas_limit: some_value(),
+            gas_price: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for TxDeployRaw {
+    fn with_every_field() -> Self {
+        TxDeployRaw {
+            from: some_value(),
+            value: some_value(),
+            contract_code: some_value(),
+            arguments: Vec::new(),
+            gas_limit: some_value(),
+            gas_price: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for TxTransferRaw {
+    fn with_every_field() -> Self {
+        TxTransferRaw {
+            from: some_value(),
+            to: some_value(),
+            value: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for TxValidatorRewardRaw {
+    fn with_every_field() -> Self {
+        TxValidatorRewardRaw {
+            to: some_value(),
+            value: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for TxExpectRaw {
+    fn with_every_field() -> Self {
+        TxExpectRaw {
+            out: Vec::new(),
+            status: some_value(),
+            logs: CheckLogsRaw::Star,
+            message: Some(some_value()),
+            gas: Some(some_value()),
+            refund: Some(some_value()),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+impl WithEveryField for CheckLogRaw {
+    fn with_every_field() -> Self {
+        CheckLogRaw {
+            address: some_value(),
+            identifier: some_value(),
+            topics: vec![some_value()],
+            data: some_value(),
+            unknown_fields: UnknownFields::new(),
+        }
+    }
+}
+
+/// The known field with the smallest edit distance, if it is close enough to be a plausible typo.
+fn closest_field(field: &str, known_fields: &[String]) -> Option<String> {
+    known_fields.iter()
+        .map(|known| (edit_distance(field, known), known))
+        .filter(|(distance, known)| *distance <= max_typo_distance(known))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known.clone())
+}
+
+fn max_typo_distance(known: &str) -> usize {
+    (known.len() / 3).max(2)
+}
+
+/// Levenshtein distance, case-insensitive so that wrong capitalization is always a close match.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -1,18 +1,85 @@
+use super::value_formatter::*;
 use super::value_interpreter::*;
 use super::value_raw::*;
 use super::context::*;
-use num_bigint::BigUint;
+use super::error::*;
+use super::source_location::*;
+use num_bigint::{BigInt, BigUint, Sign};
 use num_traits::ToPrimitive;
+use std::borrow::Cow;
 use std::fmt;
 use std::cmp::{Ord, Ordering};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use indexmap::IndexMap;
 
-pub trait InterpretableFrom<T> {
-    fn interpret_from(from: T, context: &InterpreterContext) -> Self;
+pub trait InterpretableFrom<T>: Sized {
+    fn interpret_from(from: T, context: &InterpreterContext) -> Result<Self, MandosError>;
+}
+
+/// The way back from interpreted values to their raw, serializable form.
+/// Original representations are kept where present, otherwise a canonical one is synthesized.
+pub trait IntoRaw<R> {
+    fn into_raw(self) -> R;
+}
+
+/// Converts all keys and values of a map to their raw form.
+pub fn into_raw_map<K, V, KR, VR>(from: IndexMap<K, V>) -> IndexMap<KR, VR>
+where
+    K: IntoRaw<KR>,
+    V: IntoRaw<VR>,
+    K: Hash + Eq,
+    KR: Hash + Eq,
+{
+    from.into_iter()
+        .map(|(k, v)| (k.into_raw(), v.into_raw()))
+        .collect()
+}
+
+/// Values built in code may have been given an empty original.
+/// In that case the canonical representation is used instead, unless the value is itself empty.
+fn original_or_canonical<F>(original: ValueSubTree, is_empty: bool, canonical: F) -> ValueSubTree
+where
+    F: FnOnce() -> String,
+{
+    if original.is_empty_string() && !is_empty {
+        ValueSubTree::from(canonical())
+    } else {
+        original
+    }
+}
+
+/// Interprets all keys and values of a map.
+/// Keys are compared by value, so two different spellings of the same key are an error.
+/// The order of the entries is kept.
+pub fn interpret_map<KR, VR, K, V, I>(from: I, context: &InterpreterContext) -> Result<IndexMap<K, V>, MandosError>
+where
+    I: IntoIterator<Item = (KR, VR)>,
+    K: InterpretableFrom<KR> + Hash + Eq + fmt::Display,
+    V: InterpretableFrom<VR>,
+{
+    let mut result = IndexMap::<K, V>::new();
+    for (raw_key, raw_value) in from {
+        let key = K::interpret_from(raw_key, context)?;
+        if let Some((existing, _)) = result.get_key_value(&key) {
+            return Err(MandosError::DuplicateKey {
+                first: existing.to_string(),
+                second: key.to_string(),
+            });
+        }
+        let value = V::interpret_from(raw_value, context)?;
+        result.insert(key, value);
+    }
+    Ok(result)
 }
 
 #[derive(Clone, Debug)]
 pub struct BytesValue {
     pub value: Vec<u8>,
+
+    /// Set instead of `value` when a `file:` value is interpreted with lazy file loading.
+    pub lazy_file: Option<PathBuf>,
+
     pub original: ValueSubTree,
 }
 
@@ -20,48 +87,130 @@ impl BytesValue {
     pub fn empty() -> Self {
         BytesValue {
             value: Vec::new(),
-            original: ValueSubTree::Str(String::default())
+            lazy_file: None,
+            original: ValueSubTree::default()
+        }
+    }
+
+    /// The bytes of the value, without reading anything: a lazily loaded file that was not read is an error.
+    pub fn loaded(&self) -> Result<&[u8], MandosError> {
+        match &self.lazy_file {
+            Some(path) => Err(MandosError::FileNotLoaded(path.clone()).at(self.original.location())),
+            None => Ok(self.value.as_slice()),
+        }
+    }
+
+    /// The bytes of the value, reading the lazily loaded file first if there is one.
+    pub fn load(&self, context: &InterpreterContext) -> Result<Cow<'_, [u8]>, MandosError> {
+        match &self.lazy_file {
+            Some(path) => read_file(path.clone(), context).map(Cow::Owned),
+            None => Ok(Cow::Borrowed(self.value.as_slice())),
         }
     }
 }
 
+/// The original is synthesized by `format_value`, so it interprets back to the same bytes.
 impl From<Vec<u8>> for BytesValue {
     fn from(v: Vec<u8>) -> Self {
         BytesValue {
+            original: ValueSubTree::from(format_value(&v, FormatHint::Auto)),
             value: v,
-            original: ValueSubTree::Str(String::default())
+            lazy_file: None,
         }
     }
 }
 
-impl InterpretableFrom<ValueSubTree> for BytesValue {
-    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Self {
+impl From<&[u8]> for BytesValue {
+    fn from(v: &[u8]) -> Self {
+        BytesValue::from(v.to_vec())
+    }
+}
+
+/// The bytes of the string, written as `str:...` where possible.
+impl From<&str> for BytesValue {
+    fn from(s: &str) -> Self {
         BytesValue {
-            value: interpret_subtree(&from, context),
-            original: from,
+            value: s.as_bytes().to_vec(),
+            lazy_file: None,
+            original: ValueSubTree::from(format_value(s.as_bytes(), FormatHint::Str)),
         }
     }
 }
 
+impl InterpretableFrom<ValueSubTree> for BytesValue {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        if let ValueSubTree::Str(s, _) = &from {
+            if let Some(path) = lazy_file_path(s, context) {
+                return Ok(BytesValue {
+                    value: Vec::new(),
+                    lazy_file: Some(path),
+                    original: from,
+                });
+            }
+        }
+
+        Ok(BytesValue {
+            value: interpret_subtree(&from, context)?,
+            lazy_file: None,
+            original: from,
+        })
+    }
+}
+
+impl IntoRaw<ValueSubTree> for BytesValue {
+    fn into_raw(self) -> ValueSubTree {
+        let value = self.value;
+        original_or_canonical(self.original, value.is_empty(), || format_value(&value, FormatHint::Auto))
+    }
+}
+
 impl fmt::Display for BytesValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.original.fmt(f)
     }
 }
 
-#[derive(Debug)]
+impl Locatable for BytesValue {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct BigUintValue {
     pub value: BigUint,
     pub original: ValueSubTree,
 }
 
-impl InterpretableFrom<ValueSubTree> for BigUintValue {
-    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Self {
-        let bytes = interpret_subtree(&from, context);
+impl From<BigUint> for BigUintValue {
+    fn from(value: BigUint) -> Self {
         BigUintValue {
+            original: ValueSubTree::from(value.to_string()),
+            value,
+        }
+    }
+}
+
+impl From<u64> for BigUintValue {
+    fn from(value: u64) -> Self {
+        BigUintValue::from(BigUint::from(value))
+    }
+}
+
+impl InterpretableFrom<ValueSubTree> for BigUintValue {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_subtree(&from, context)?;
+        Ok(BigUintValue {
             value: BigUint::from_bytes_be(&bytes),
             original: from,
-        }
+        })
+    }
+}
+
+impl IntoRaw<ValueSubTree> for BigUintValue {
+    fn into_raw(self) -> ValueSubTree {
+        let value = self.value;
+        original_or_canonical(self.original, value.bits() == 0, || value.to_string())
     }
 }
 
@@ -71,40 +220,255 @@ impl fmt::Display for BigUintValue {
     }
 }
 
-#[derive(Debug)]
+impl Locatable for BigUintValue {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct U64Value {
     pub value: u64,
     pub original: ValueSubTree,
 }
 
+impl From<u64> for U64Value {
+    fn from(value: u64) -> Self {
+        U64Value {
+            value,
+            original: ValueSubTree::from(value.to_string()),
+        }
+    }
+}
+
 impl InterpretableFrom<ValueSubTree> for U64Value {
-    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Self {
-        let bytes = interpret_subtree(&from, context);
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_subtree(&from, context)?;
         let bu = BigUint::from_bytes_be(&bytes);
-        U64Value {
-            value: bu.to_u64().unwrap(),
-            original: from,
+        match bu.to_u64() {
+            Some(value) => Ok(U64Value {
+                value,
+                original: from,
+            }),
+            None => Err(overflow(&from, "u64")),
         }
     }
 }
 
+impl IntoRaw<ValueSubTree> for U64Value {
+    fn into_raw(self) -> ValueSubTree {
+        let value = self.value;
+        original_or_canonical(self.original, value == 0, || value.to_string())
+    }
+}
+
 impl fmt::Display for U64Value {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.original.fmt(f)
     }
 }
 
+impl Locatable for U64Value {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct U32Value {
+    pub value: u32,
+    pub original: ValueSubTree,
+}
+
+impl InterpretableFrom<ValueSubTree> for U32Value {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_subtree(&from, context)?;
+        let bu = BigUint::from_bytes_be(&bytes);
+        match bu.to_u32() {
+            Some(value) => Ok(U32Value {
+                value,
+                original: from,
+            }),
+            None => Err(overflow(&from, "u32")),
+        }
+    }
+}
+
+impl fmt::Display for U32Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.original.fmt(f)
+    }
+}
+
+impl Locatable for U32Value {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct U8Value {
+    pub value: u8,
+    pub original: ValueSubTree,
+}
+
+impl InterpretableFrom<ValueSubTree> for U8Value {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_subtree(&from, context)?;
+        let bu = BigUint::from_bytes_be(&bytes);
+        match bu.to_u8() {
+            Some(value) => Ok(U8Value {
+                value,
+                original: from,
+            }),
+            None => Err(overflow(&from, "u8")),
+        }
+    }
+}
+
+impl fmt::Display for U8Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.original.fmt(f)
+    }
+}
+
+impl Locatable for U8Value {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+/// Plain decimals such as `"255"` and unsigned values such as `"u8:255"` are read as non-negative.
+/// Anything else, e.g. `"-1"`, `"0xff"` or `"i8:-1"`, is read from its bytes as two's complement.
+#[derive(Clone, Debug)]
+pub struct BigIntValue {
+    pub value: BigInt,
+    pub original: ValueSubTree,
+}
+
+impl InterpretableFrom<ValueSubTree> for BigIntValue {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        Ok(BigIntValue {
+            value: interpret_big_int(&from, context)?,
+            original: from,
+        })
+    }
+}
+
+impl fmt::Display for BigIntValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.original.fmt(f)
+    }
+}
+
+impl Locatable for BigIntValue {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+/// Read like `BigIntValue`.
+#[derive(Clone, Debug)]
+pub struct I64Value {
+    pub value: i64,
+    pub original: ValueSubTree,
+}
+
+impl InterpretableFrom<ValueSubTree> for I64Value {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bi = interpret_big_int(&from, context)?;
+        match bi.to_i64() {
+            Some(value) => Ok(I64Value {
+                value,
+                original: from,
+            }),
+            None => Err(overflow(&from, "i64")),
+        }
+    }
+}
+
+impl fmt::Display for I64Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.original.fmt(f)
+    }
+}
+
+impl Locatable for I64Value {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BoolValue {
+    pub value: bool,
+    pub original: ValueSubTree,
+}
+
+impl InterpretableFrom<ValueSubTree> for BoolValue {
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_subtree(&from, context)?;
+        let value = match bytes.as_slice() {
+            [] => false,
+            [1] => true,
+            _ => return Err(MandosError::InvalidBool(from.to_string()).at(from.location())),
+        };
+        Ok(BoolValue {
+            value,
+            original: from,
+        })
+    }
+}
+
+impl fmt::Display for BoolValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.original.fmt(f)
+    }
+}
+
+impl Locatable for BoolValue {
+    fn location(&self) -> Option<&SourceLocation> {
+        self.original.location()
+    }
+}
+
+fn interpret_big_int(from: &ValueSubTree, context: &InterpreterContext) -> Result<BigInt, MandosError> {
+    let bytes = interpret_subtree(from, context)?;
+    match from {
+        ValueSubTree::Str(s, _) if is_unsigned_value(s) => Ok(BigInt::from_bytes_be(Sign::Plus, &bytes)),
+        _ => Ok(BigInt::from_signed_bytes_be(&bytes)),
+    }
+}
+
+fn overflow(from: &ValueSubTree, type_name: &'static str) -> MandosError {
+    MandosError::IntegerOverflow {
+        value: from.to_string(),
+        type_name,
+    }.at(from.location())
+}
+
 #[derive(Clone, Debug)]
 pub struct BytesKey {
     pub value: Vec<u8>,
     pub original: String,
 }
 
+/// The original is synthesized by `format_value`, so it interprets back to the same bytes.
 impl From<Vec<u8>> for BytesKey {
     fn from(v: Vec<u8>) -> Self {
         BytesKey {
+            original: format_value(&v, FormatHint::Auto),
             value: v,
-            original: String::default()
+        }
+    }
+}
+
+/// The bytes of the string, written as `str:...` where possible.
+impl From<&str> for BytesKey {
+    fn from(s: &str) -> Self {
+        BytesKey {
+            value: s.as_bytes().to_vec(),
+            original: format_value(s.as_bytes(), FormatHint::Str),
         }
     }
 }
@@ -117,9 +481,15 @@ impl PartialEq for BytesKey {
 
 impl Eq for BytesKey {}
 
+impl Hash for BytesKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
 impl PartialOrd for BytesKey {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.value.partial_cmp(&other.value)
+        Some(self.cmp(other))
     }
 }
 
@@ -130,11 +500,21 @@ impl Ord for BytesKey {
 }
 
 impl InterpretableFrom<String> for BytesKey {
-    fn interpret_from(from: String, context: &InterpreterContext) -> Self {
-        let bytes = interpret_string(&from, context);
-        BytesKey {
+    fn interpret_from(from: String, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let bytes = interpret_string(&from, context)?;
+        Ok(BytesKey {
             value: bytes,
             original: from,
+        })
+    }
+}
+
+impl IntoRaw<String> for BytesKey {
+    fn into_raw(self) -> String {
+        if self.original.is_empty() && !self.value.is_empty() {
+            format_value(&self.value, FormatHint::Auto)
+        } else {
+            self.original
         }
     }
 }
@@ -1,16 +1,19 @@
 use super::value::*;
 use super::value_raw::*;
 use super::context::*;
-use num_bigint::BigUint;
+use super::error::*;
+use super::source_location::*;
+use num_bigint::{BigInt, BigUint};
 use std::fmt;
 
 pub trait Checkable<V> {
     fn check(&self, value: V) -> bool;
 }
 
+/// A lazily loaded file that was not read matches nothing, see `BytesValue::loaded`.
 impl Checkable<&[u8]> for BytesValue {
     fn check(&self, value: &[u8]) -> bool {
-        self.value.as_slice() == value
+        self.loaded().is_ok_and(|bytes| bytes == value)
     }
 }
 
@@ -26,8 +29,39 @@ impl Checkable<u64> for U64Value {
     }
 }
 
-#[derive(Debug)]
+impl Checkable<u32> for U32Value {
+    fn check(&self, value: u32) -> bool {
+        self.value == value
+    }
+}
+
+impl Checkable<u8> for U8Value {
+    fn check(&self, value: u8) -> bool {
+        self.value == value
+    }
+}
+
+impl Checkable<&BigInt> for BigIntValue {
+    fn check(&self, value: &BigInt) -> bool {
+        &self.value == value
+    }
+}
+
+impl Checkable<i64> for I64Value {
+    fn check(&self, value: i64) -> bool {
+        self.value == value
+    }
+}
+
+impl Checkable<bool> for BoolValue {
+    fn check(&self, value: bool) -> bool {
+        self.value == value
+    }
+}
+
+#[derive(Clone, Debug, Default)]
 pub enum CheckValue<T> {
+    #[default]
     DefaultStar,
     Star,
     Equal(T),
@@ -43,23 +77,28 @@ impl<T: InterpretableFrom<ValueSubTree>> CheckValue<T> {
     }
 }
 
-impl<T: InterpretableFrom<ValueSubTree>> Default for CheckValue<T> {
-    fn default() -> Self {
-        CheckValue::DefaultStar
-    }
-}
-
 impl<T: InterpretableFrom<ValueSubTree>> InterpretableFrom<ValueSubTree> for CheckValue<T> {
-    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Self {
-        if let ValueSubTree::Str(s) = &from {
-            if s == "" {
-                return CheckValue::DefaultStar;
+    fn interpret_from(from: ValueSubTree, context: &InterpreterContext) -> Result<Self, MandosError> {
+        if let ValueSubTree::Str(s, _) = &from {
+            if s.is_empty() {
+                return Ok(CheckValue::DefaultStar);
             } else if s == "*" {
-                return CheckValue::Star;
+                return Ok(CheckValue::Star);
             }
         }
 
-        CheckValue::Equal(T::interpret_from(from, context))
+        Ok(CheckValue::Equal(T::interpret_from(from, context)?))
+    }
+}
+
+/// `DefaultStar` goes back to the empty string, so that it can be skipped when serializing.
+impl<T: IntoRaw<ValueSubTree>> IntoRaw<ValueSubTree> for CheckValue<T> {
+    fn into_raw(self) -> ValueSubTree {
+        match self {
+            CheckValue::DefaultStar => ValueSubTree::from(""),
+            CheckValue::Star => ValueSubTree::from("*"),
+            CheckValue::Equal(eq_value) => eq_value.into_raw(),
+        }
     }
 }
 
@@ -75,6 +114,15 @@ impl<T: fmt::Display> fmt::Display for CheckValue<T> {
     }
 }
 
+impl<T: Locatable> Locatable for CheckValue<T> {
+    fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            CheckValue::Star | CheckValue::DefaultStar => None,
+            CheckValue::Equal(eq_value) => eq_value.location(),
+        }
+    }
+}
+
 impl<V, T> Checkable<V> for CheckValue<T>
 where T: Checkable<V>
 {
@@ -108,7 +156,8 @@ mod tests {
     fn check_bytes() {
         let bv = BytesValue {
             value: b"abc".to_vec(),
-            original: ValueSubTree::Str("abc".to_string()),
+            lazy_file: None,
+            original: ValueSubTree::from("abc"),
         };
         assert!(bv.check(&b"abc"[..]));
 
@@ -117,13 +166,20 @@ mod tests {
 
         let cb_star: CheckValue<BytesValue> = CheckValue::Star;
         assert!(cb_star.check(&b"anything_really"[..]));
+
+        let lazy = BytesValue {
+            value: Vec::new(),
+            lazy_file: Some("code.wasm".into()),
+            original: ValueSubTree::from("file:code.wasm"),
+        };
+        assert!(!lazy.check(&b""[..]));
     }
 
     #[test]
     fn check_u64() {
         let u64v = U64Value {
             value: 123,
-            original: ValueSubTree::Str("123".to_string()),
+            original: ValueSubTree::from("123"),
         };
         assert!(u64v.check(123u64));
 
@@ -133,4 +189,30 @@ mod tests {
         let cb_star: CheckValue<U64Value> = CheckValue::Star;
         assert!(cb_star.check(1234567890));
     }
+
+    #[test]
+    fn check_big_int() {
+        let biv = BigIntValue {
+            value: BigInt::from(-5),
+            original: ValueSubTree::from("-5"),
+        };
+        assert!(biv.check(&BigInt::from(-5)));
+        assert!(!biv.check(&BigInt::from(5)));
+
+        let cb_eq = CheckValue::Equal(biv);
+        assert!(cb_eq.check(&BigInt::from(-5)));
+    }
+
+    #[test]
+    fn check_bool() {
+        let bv = BoolValue {
+            value: true,
+            original: ValueSubTree::from("true"),
+        };
+        assert!(bv.check(true));
+        assert!(!bv.check(false));
+
+        let cb_star: CheckValue<BoolValue> = CheckValue::Star;
+        assert!(cb_star.check(false));
+    }
 }
@@ -0,0 +1,114 @@
+use super::context::VM_TYPE_WASM;
+use num_bigint::BigUint;
+
+/// What the bytes passed to `format_value` are expected to represent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatHint {
+    /// Pick whatever representation looks most readable.
+    Auto,
+    Address,
+    Str,
+    Number,
+    U64,
+    Bool,
+}
+
+/// Produces a Mandos expression that interprets back to exactly `bytes`.
+/// The hint is followed whenever the bytes can be represented that way, otherwise this falls back to hex.
+/// Smart contract addresses are recognized by the WebAssembly VM type, see `format_value_for_vm`.
+pub fn format_value(bytes: &[u8], hint: FormatHint) -> String {
+    format_value_for_vm(bytes, hint, &VM_TYPE_WASM)
+}
+
+/// Same as `format_value`, writing addresses with the given VM type as `sc:`.
+/// Pass `InterpreterContext::vm_type` to round-trip through a context that uses a different VM.
+pub fn format_value_for_vm(bytes: &[u8], hint: FormatHint, vm_type: &[u8]) -> String {
+    let formatted = match hint {
+        FormatHint::Auto => format_auto(bytes, vm_type),
+        FormatHint::Address => format_address(bytes, vm_type),
+        FormatHint::Str => format_str(bytes),
+        FormatHint::Number => format_number(bytes),
+        FormatHint::U64 => format_u64(bytes),
+        FormatHint::Bool => format_bool(bytes),
+    };
+    formatted.unwrap_or_else(|| format_hex(bytes))
+}
+
+fn format_auto(bytes: &[u8], vm_type: &[u8]) -> Option<String> {
+    if bytes.is_empty() {
+        return Some(String::new());
+    }
+    if bytes.len() == 32 {
+        if let Some(address) = format_address(bytes, vm_type) {
+            return Some(address);
+        }
+    }
+    if bytes.iter().all(|&b| is_printable(b)) {
+        return format_str(bytes);
+    }
+    if bytes.len() <= 8 {
+        return format_number(bytes);
+    }
+    None
+}
+
+fn format_address(bytes: &[u8], vm_type: &[u8]) -> Option<String> {
+    if bytes.len() != 32 {
+        return None;
+    }
+    let (leading_zeros, rest) = bytes.split_at(8);
+    if leading_zeros.iter().all(|&b| b == 0) && rest.starts_with(vm_type) {
+        let name = &rest[vm_type.len()..];
+        return format_address_name(name).map(|name| format!("sc:{}", name));
+    }
+    format_address_name(bytes).map(|name| format!("address:{}", name))
+}
+
+/// The name part of `address:` or `sc:`, without the `_` padding.
+fn format_address_name(bytes: &[u8]) -> Option<&str> {
+    if !bytes.iter().all(|&b| is_printable(b)) {
+        return None;
+    }
+    let name = std::str::from_utf8(bytes).ok()?;
+    Some(name.trim_end_matches('_'))
+}
+
+fn format_str(bytes: &[u8]) -> Option<String> {
+    let s = std::str::from_utf8(bytes).ok()?;
+    if s.contains('|') || s.chars().any(char::is_control) {
+        return None;
+    }
+    Some(format!("str:{}", s))
+}
+
+fn format_number(bytes: &[u8]) -> Option<String> {
+    // leading zeros would be lost
+    if bytes.first() == Some(&0) {
+        return None;
+    }
+    Some(BigUint::from_bytes_be(bytes).to_string())
+}
+
+fn format_u64(bytes: &[u8]) -> Option<String> {
+    if bytes.len() != 8 {
+        return format_number(bytes);
+    }
+    Some(format!("u64:{}", BigUint::from_bytes_be(bytes)))
+}
+
+fn format_bool(bytes: &[u8]) -> Option<String> {
+    match bytes {
+        [] => Some("false".to_string()),
+        [1] => Some("true".to_string()),
+        _ => None,
+    }
+}
+
+fn format_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Printable ASCII, except `|`, which would split the expression.
+fn is_printable(b: u8) -> bool {
+    (0x20..0x7f).contains(&b) && b != b'|'
+}
@@ -1,13 +1,22 @@
 use num_bigint::{BigInt, BigUint, Sign};
 use num_traits::identities::Zero;
+use sha3::{Digest, Keccak256};
+use std::path::PathBuf;
 use super::context::*;
+use super::bech32_address::*;
+use super::error::*;
+use super::value_prefix::*;
 use super::value_raw::*;
 
 const STR_PREFIXES: [&str; 3] = ["str:", "``", "''"];
 
 const ADDR_PREFIX: &str = "address:";
+const SC_ADDR_PREFIX: &str = "sc:";
+const BECH32_PREFIX: &str = "bech32:";
 const FILE_PREFIX: &str = "file:";
-// const keccak256Prefix = "keccak256:"
+const KECCAK256_PREFIX: &str = "keccak256:";
+const NESTED_PREFIX: &str = "nested:";
+const BIGUINT_PREFIX: &str = "biguint:";
 
 const U64_PREFIX: &str = "u64:";
 const U32_PREFIX: &str = "u32:";
@@ -18,29 +27,41 @@ const I32_PREFIX: &str = "i32:";
 const I16_PREFIX: &str = "i16:";
 const I8_PREFIX : &str = "i8:";
 
-pub fn interpret_subtree(vst: &ValueSubTree, context: &InterpreterContext) -> Vec<u8> {
+pub fn interpret_subtree(vst: &ValueSubTree, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
     match vst {
-        ValueSubTree::Str(s) => interpret_string(s, context),
+        ValueSubTree::Str(s, location) => interpret_string(s, context)
+            .map_err(|error| error.at(location.as_ref())),
         ValueSubTree::List(l) => {
             let mut concat = Vec::<u8>::new();
             for item in l.iter() {
-                concat.extend_from_slice(interpret_subtree(item, context).as_slice());
+                concat.extend_from_slice(interpret_subtree(item, context)?.as_slice());
             }
-            concat
+            Ok(concat)
         },
         ValueSubTree::Map(m) => {
             let mut concat = Vec::<u8>::new();
             for (_, value) in m.iter() {
-                concat.extend_from_slice(interpret_subtree(value, context).as_slice());
+                concat.extend_from_slice(interpret_subtree(value, context)?.as_slice());
             }
-            concat
+            Ok(concat)
         }
     }
 }
 
-pub fn interpret_string(s: &str, context: &InterpreterContext) -> Vec<u8> {
+pub fn interpret_string(s: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
     if s.is_empty() {
-        return Vec::new();
+        return Ok(Vec::new());
+    }
+
+    // the rest of the string is the path, even if it contains '|'
+    if let Some(stripped) = s.strip_prefix(FILE_PREFIX) {
+        return load_file(stripped, context);
+    }
+
+    // hashes everything that follows, including any concatenation
+    if let Some(stripped) = s.strip_prefix(KECCAK256_PREFIX) {
+        let arg = interpret_string(stripped, context)?;
+        return Ok(keccak256(arg.as_slice()));
     }
 
     // concatenate values of different formats
@@ -48,151 +69,209 @@ pub fn interpret_string(s: &str, context: &InterpreterContext) -> Vec<u8> {
     if split_parts.len() > 1 {
         let mut result = Vec::<u8>::new();
         for part in split_parts.iter() {
-            result.extend_from_slice(interpret_string(part, context).as_slice());
+            result.extend_from_slice(interpret_string(part, context)?.as_slice());
         }
-        return result;
+        return Ok(result);
+    }
+
+    interpret_part(s, context)
+}
+
+fn interpret_part(s: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+    if let Some((prefix, handler)) = context.prefix_handler(s) {
+        return handler.interpret(&s[prefix.len()..], context);
     }
 
     if s == "true" {
-        return [1u8].to_vec();
+        return Ok([1u8].to_vec());
     }
 
     if s == "false" {
-        return Vec::new()
+        return Ok(Vec::new());
     }
 
     for str_prefix in STR_PREFIXES.iter() {
-        if s.starts_with(str_prefix) {
-            return s[str_prefix.len() .. ].as_bytes().to_vec()
+        if let Some(stripped) = s.strip_prefix(str_prefix) {
+            return Ok(stripped.as_bytes().to_vec());
         }
     }
-    
-    if s.starts_with(ADDR_PREFIX) {
-        return address(&s[ADDR_PREFIX.len() .. ]);
+
+    if let Some(stripped) = s.strip_prefix(ADDR_PREFIX) {
+        return Ok(address(stripped));
+    }
+
+    if let Some(stripped) = s.strip_prefix(SC_ADDR_PREFIX) {
+        return Ok(sc_address(stripped, context.vm_type()));
+    }
+
+    if let Some(stripped) = s.strip_prefix(BECH32_PREFIX) {
+        return Ok(bech32_decode(stripped)?.to_vec());
+    }
+
+    if let Some(stripped) = s.strip_prefix(NESTED_PREFIX) {
+        let nested = interpret_string(stripped, context)?;
+        return Ok(length_prefixed(nested.as_slice()));
     }
 
-    if s.starts_with(FILE_PREFIX) {
-        return s.as_bytes().to_vec();
+    if let Some(stripped) = s.strip_prefix(BIGUINT_PREFIX) {
+        // the two's complement bytes of a signed value would be read back as a different number
+        if is_signed(stripped) {
+            return Err(MandosError::SignedBigUint(s.to_string()));
+        }
+        let bu = BigUint::from_bytes_be(interpret_string(stripped, context)?.as_slice());
+        return Ok(length_prefixed(big_uint_to_bytes_be(&bu).as_slice()));
     }
 
     if let Some(fixed_width) = try_parse_fixed_width(s) {
         return fixed_width;
     }
 
-    if s.starts_with('+') {
-        let bi = BigInt::from_bytes_be(Sign::Plus, parse_unsigned(&s[1..]).as_slice());
-        return big_int_to_bytes_be(&bi);
+    if let Some((prefix, _)) = split_prefix(s) {
+        return Err(MandosError::UnknownPrefix {
+            prefix: prefix.to_string(),
+            value: s.to_string(),
+        });
+    }
+
+    if let Some(stripped) = s.strip_prefix('+') {
+        let bi = BigInt::from_bytes_be(Sign::Plus, parse_unsigned(stripped)?.as_slice());
+        return Ok(big_int_to_bytes_be(&bi));
     }
 
-    if s.starts_with('-') {
-        let bi = BigInt::from_bytes_be(Sign::Minus, parse_unsigned(&s[1..]).as_slice());
-        return big_int_to_bytes_be(&bi);
+    if let Some(stripped) = s.strip_prefix('-') {
+        let bi = BigInt::from_bytes_be(Sign::Minus, parse_unsigned(stripped)?.as_slice());
+        return Ok(big_int_to_bytes_be(&bi));
     }
 
     parse_unsigned(s)
 }
 
-fn try_parse_fixed_width(s: &str) -> Option<Vec<u8>> {
-    if s.starts_with(U64_PREFIX) {
-        return Some(parse_fixed_width_unsigned(&s[U64_PREFIX.len()..], 8));
+fn is_signed(s: &str) -> bool {
+    s.starts_with('-') || s.starts_with('+') ||
+        [I64_PREFIX, I32_PREFIX, I16_PREFIX, I8_PREFIX].iter().any(|prefix| s.starts_with(prefix))
+}
+
+fn try_parse_fixed_width(s: &str) -> Option<Result<Vec<u8>, MandosError>> {
+    if let Some(stripped) = s.strip_prefix(U64_PREFIX) {
+        return Some(parse_fixed_width_unsigned(stripped, 8));
     }
 
-    if s.starts_with(U32_PREFIX) {
-        return Some(parse_fixed_width_unsigned(&s[U32_PREFIX.len()..], 4));
+    if let Some(stripped) = s.strip_prefix(U32_PREFIX) {
+        return Some(parse_fixed_width_unsigned(stripped, 4));
     }
 
-    if s.starts_with(U16_PREFIX) {
-        return Some(parse_fixed_width_unsigned(&s[U16_PREFIX.len()..], 2));
+    if let Some(stripped) = s.strip_prefix(U16_PREFIX) {
+        return Some(parse_fixed_width_unsigned(stripped, 2));
     }
 
-    if s.starts_with(U8_PREFIX) {
-        return Some(parse_fixed_width_unsigned(&s[U8_PREFIX.len()..], 1));
+    if let Some(stripped) = s.strip_prefix(U8_PREFIX) {
+        return Some(parse_fixed_width_unsigned(stripped, 1));
     }
 
-    if s.starts_with(I64_PREFIX) {
-        return Some(parse_fixed_width_signed(&s[I64_PREFIX.len()..], 8));
+    if let Some(stripped) = s.strip_prefix(I64_PREFIX) {
+        return Some(parse_fixed_width_signed(stripped, 8));
     }
 
-    if s.starts_with(I32_PREFIX) {
-        return Some(parse_fixed_width_signed(&s[I32_PREFIX.len()..], 4));
+    if let Some(stripped) = s.strip_prefix(I32_PREFIX) {
+        return Some(parse_fixed_width_signed(stripped, 4));
     }
 
-    if s.starts_with(I16_PREFIX) {
-        return Some(parse_fixed_width_signed(&s[I16_PREFIX.len()..], 2));
+    if let Some(stripped) = s.strip_prefix(I16_PREFIX) {
+        return Some(parse_fixed_width_signed(stripped, 2));
     }
 
-    if s.starts_with(I8_PREFIX) {
-        return Some(parse_fixed_width_signed(&s[I8_PREFIX.len()..], 1));
+    if let Some(stripped) = s.strip_prefix(I8_PREFIX) {
+        return Some(parse_fixed_width_signed(stripped, 1));
     }
 
     None
 }
 
-fn parse_fixed_width_signed(s: &str, length: usize) -> Vec<u8> {
-    if s.starts_with('-') {
+fn parse_fixed_width_signed(s: &str, length: usize) -> Result<Vec<u8>, MandosError> {
+    if let Some(stripped) = s.strip_prefix('-') {
         let mut result = vec![0xffu8; length];
-        let bi = BigInt::from_bytes_be(Sign::Minus, parse_unsigned(&s[1..]).as_slice());
+        let bi = BigInt::from_bytes_be(Sign::Minus, parse_unsigned(stripped)?.as_slice());
         let bytes = bi.to_signed_bytes_be();
-        assert!(
-            bytes.len() <= length,
-            "representation of {} does not fit in {} bytes",
-            s, length);
+        if bytes.len() > length {
+            return Err(MandosError::FixedWidthOverflow {
+                value: s.to_string(),
+                width: length,
+            });
+        }
         let offset = length - bytes.len();
         if !bytes.is_empty() {
             result[offset..].clone_from_slice(&bytes[..]);
         }
-        result
+        Ok(result)
     } else {
-        let s = if s.starts_with('+') { &s[1..] } else { s };
-        let result = parse_fixed_width_unsigned(s, length);
+        let s = s.strip_prefix('+').unwrap_or(s);
+        let result = parse_fixed_width_unsigned(s, length)?;
         if !result.is_empty() && result[0] >> 7 == 1 {
-            panic!("representation of {} does not fit in {} bytes",
-                s, length);
+            return Err(MandosError::FixedWidthOverflow {
+                value: s.to_string(),
+                width: length,
+            });
         }
-        result
+        Ok(result)
     }
 }
 
-fn parse_fixed_width_unsigned(s: &str, length: usize) -> Vec<u8> {
-    let parsed = parse_unsigned(s);
-    assert!(
-        parsed.len() <= length,
-        "representation of {} does not fit in {} bytes",
-        s, length);
+fn parse_fixed_width_unsigned(s: &str, length: usize) -> Result<Vec<u8>, MandosError> {
+    let parsed = parse_unsigned(s)?;
+    if parsed.len() > length {
+        return Err(MandosError::FixedWidthOverflow {
+            value: s.to_string(),
+            width: length,
+        });
+    }
 
     let mut result = vec![0u8; length];
     let offset = length - parsed.len();
     if !parsed.is_empty() {
         result[offset..].clone_from_slice(&parsed[..]);
     }
-    result
+    Ok(result)
 }
 
-fn parse_unsigned(s: &str) -> Vec<u8> {
+/// Base 10 digits, possibly with `_` or `,` separators, and no sign or prefix.
+fn is_unsigned_decimal(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit()) &&
+        s.chars().all(|c| c.is_ascii_digit() || c == '_' || c == ',')
+}
+
+/// Plain decimals, and values that are unsigned by their prefix, e.g. `u8:255` or `biguint:5`.
+pub(crate) fn is_unsigned_value(s: &str) -> bool {
+    is_unsigned_decimal(s) ||
+        [U64_PREFIX, U32_PREFIX, U16_PREFIX, U8_PREFIX, BIGUINT_PREFIX].iter().any(|prefix| s.starts_with(prefix))
+}
+
+fn parse_unsigned(s: &str) -> Result<Vec<u8>, MandosError> {
     let clean = s.replace(&['_', ','][..], "");
     if clean.starts_with("0x") || clean.starts_with("0X") {
         let clean = &clean[2..];
-        return if clean.len() % 2 == 0 {
-            hex::decode(clean).unwrap()
+        let decoded = if clean.len() % 2 == 0 {
+            hex::decode(clean)
         } else {
             let even_bytes = format!("0{}", clean);
-            hex::decode(&even_bytes[..]).unwrap()
-        }
+            hex::decode(&even_bytes[..])
+        };
+        return decoded.map_err(|_| MandosError::InvalidHex(s.to_string()));
     }
 
     if clean.starts_with("0b") || clean.starts_with("0B") {
         let clean = &clean[2..];
         if clean.is_empty() {
-            return Vec::new();
+            return Ok(Vec::new());
         }
-        let bu = BigUint::parse_bytes(clean.as_bytes(), 2).unwrap();
-        return big_uint_to_bytes_be(&bu);
+        return match BigUint::parse_bytes(clean.as_bytes(), 2) {
+            Some(bu) => Ok(big_uint_to_bytes_be(&bu)),
+            None => Err(MandosError::InvalidBinary(s.to_string())),
+        };
     }
 
-    if let Some(bu) = BigUint::parse_bytes(clean.as_bytes(), 10) {
-        big_uint_to_bytes_be(&bu)
-    } else {
-        panic!("Could not parse base 10 number: {}", clean)
+    match BigUint::parse_bytes(clean.as_bytes(), 10) {
+        Some(bu) => Ok(big_uint_to_bytes_be(&bu)),
+        None => Err(MandosError::InvalidDecimal(s.to_string())),
     }
 }
 
@@ -212,6 +291,40 @@ fn big_int_to_bytes_be(bi: &BigInt) -> Vec<u8> {
     }
 }
 
+/// A file that cannot be read is an error in every mode, since carrying on would mean running empty code.
+fn load_file(file_path: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+    read_file(context.context_path().join(file_path), context)
+}
+
+pub(crate) fn read_file(path: PathBuf, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+    context.file_loader()
+        .load(&path)
+        .map_err(|error| MandosError::FileRead {
+            path,
+            error: error.to_string(),
+        })
+}
+
+/// Resolved path of a whole `file:` value, if it is to be loaded lazily.
+pub(crate) fn lazy_file_path(s: &str, context: &InterpreterContext) -> Option<PathBuf> {
+    if !context.lazy_file_loading() {
+        return None;
+    }
+    s.strip_prefix(FILE_PREFIX)
+        .map(|file_path| context.context_path().join(file_path))
+}
+
+/// Nested encoding: u32 big endian length, followed by the bytes.
+fn length_prefixed(bytes: &[u8]) -> Vec<u8> {
+    let mut result = (bytes.len() as u32).to_be_bytes().to_vec();
+    result.extend_from_slice(bytes);
+    result
+}
+
+fn keccak256(data: &[u8]) -> Vec<u8> {
+    Keccak256::digest(data).to_vec()
+}
+
 fn address(s: &str) -> Vec<u8> {
     let bytes = s.as_bytes();
     if bytes.len() > 32 {
@@ -221,3 +334,17 @@ fn address(s: &str) -> Vec<u8> {
     result[.. bytes.len()].copy_from_slice(bytes);
     result
 }
+
+const SC_ADDRESS_NUM_LEADING_ZEROS: usize = 8;
+
+/// Smart contract addresses: 8 zero bytes, the VM type, then the name padded with `_`.
+fn sc_address(s: &str, vm_type: &[u8]) -> Vec<u8> {
+    let mut result = vec![b'_'; 32];
+    let vm_type_end = SC_ADDRESS_NUM_LEADING_ZEROS + vm_type.len();
+    result[.. SC_ADDRESS_NUM_LEADING_ZEROS].copy_from_slice(&[0u8; SC_ADDRESS_NUM_LEADING_ZEROS]);
+    result[SC_ADDRESS_NUM_LEADING_ZEROS .. vm_type_end].copy_from_slice(vm_type);
+    let bytes = s.as_bytes();
+    let name_len = bytes.len().min(32 - vm_type_end);
+    result[vm_type_end .. vm_type_end + name_len].copy_from_slice(&bytes[.. name_len]);
+    result
+}
@@ -0,0 +1,39 @@
+use super::context::*;
+use super::error::*;
+
+/// Interprets values with a user-defined prefix, such as `fp18:`.
+/// Receives the part of the value after the prefix.
+///
+/// Registered handlers take precedence over the built-in prefixes.
+/// Closures with the signature of `interpret` are handlers too.
+pub trait ValuePrefixHandler {
+    fn interpret(&self, value: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError>;
+}
+
+impl<F> ValuePrefixHandler for F
+where
+    F: Fn(&str, &InterpreterContext) -> Result<Vec<u8>, MandosError>,
+{
+    fn interpret(&self, value: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+        self(value, context)
+    }
+}
+
+impl ValuePrefixHandler for Box<dyn ValuePrefixHandler> {
+    fn interpret(&self, value: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+        self.as_ref().interpret(value, context)
+    }
+}
+
+/// Splits off something that looks like a value prefix, e.g. `fp18:` in `fp18:1.5`.
+pub(crate) fn split_prefix(s: &str) -> Option<(&str, &str)> {
+    let colon = s.find(':')?;
+    let name = &s[..colon];
+    let mut chars = name.chars();
+    let starts_with_letter = chars.next().is_some_and(|c| c.is_ascii_alphabetic());
+    if starts_with_letter && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Some(s.split_at(colon + 1))
+    } else {
+        None
+    }
+}
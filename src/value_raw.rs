@@ -1,20 +1,22 @@
-
-use std::collections::BTreeMap;
+use super::source_location::*;
+use indexmap::IndexMap;
 use std::fmt;
 use serde::ser::{Serialize, Serializer, SerializeSeq, SerializeMap};
 use serde::de::{self, Deserialize, Deserializer, Visitor, SeqAccess, MapAccess};
 
-#[derive(PartialEq, Clone, Debug)]
+/// Raw Mandos value, as found in the JSON.
+/// Strings remember where they were read from, once located with `locate_values`.
+#[derive(Clone, Debug)]
 pub enum ValueSubTree {
-    Str(String),
+    Str(String, Option<SourceLocation>),
     List(Vec<ValueSubTree>),
-    Map(BTreeMap<String, ValueSubTree>),
+    Map(IndexMap<String, ValueSubTree>),
 }
 
 impl ValueSubTree {
     pub fn is_empty_string(&self) -> bool {
         match self {
-            ValueSubTree::Str(s) => s.is_empty(),
+            ValueSubTree::Str(s, _) => s.is_empty(),
             _ => false,
         }
     }
@@ -22,7 +24,43 @@ impl ValueSubTree {
 
 impl Default for ValueSubTree {
     fn default() -> Self {
-        ValueSubTree::Str(String::from(""))
+        ValueSubTree::Str(String::from(""), None)
+    }
+}
+
+impl From<&str> for ValueSubTree {
+    fn from(s: &str) -> Self {
+        ValueSubTree::Str(String::from(s), None)
+    }
+}
+
+impl From<String> for ValueSubTree {
+    fn from(s: String) -> Self {
+        ValueSubTree::Str(s, None)
+    }
+}
+
+/// Source locations are not part of the value.
+/// Map entries are compared in order, since they are concatenated in that order when interpreted.
+impl PartialEq for ValueSubTree {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ValueSubTree::Str(s1, _), ValueSubTree::Str(s2, _)) => s1 == s2,
+            (ValueSubTree::List(l1), ValueSubTree::List(l2)) => l1 == l2,
+            (ValueSubTree::Map(m1), ValueSubTree::Map(m2)) => m1.len() == m2.len() && m1.iter().eq(m2.iter()),
+            _ => false,
+        }
+    }
+}
+
+/// Lists and maps are located at their first located string.
+impl Locatable for ValueSubTree {
+    fn location(&self) -> Option<&SourceLocation> {
+        match self {
+            ValueSubTree::Str(_, location) => location.as_ref(),
+            ValueSubTree::List(l) => l.iter().find_map(|item| item.location()),
+            ValueSubTree::Map(m) => m.values().find_map(|value| value.location()),
+        }
     }
 }
 
@@ -32,7 +70,7 @@ impl Serialize for ValueSubTree {
         S: Serializer,
     {
         match self {
-            ValueSubTree::Str(s) => serializer.serialize_str(s.as_str()),
+            ValueSubTree::Str(s, _) => serializer.serialize_str(s.as_str()),
             ValueSubTree::List(l) => {
                 let mut seq = serializer.serialize_seq(Some(l.len()))?;
                 for item in l {
@@ -65,7 +103,7 @@ impl<'de> Visitor<'de> for ValueSubTreeVisitor {
     where
         E: de::Error,
     {
-        Ok(ValueSubTree::Str(String::from(value)))
+        Ok(ValueSubTree::Str(String::from(value), None))
     }
 
     fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
@@ -85,7 +123,7 @@ impl<'de> Visitor<'de> for ValueSubTreeVisitor {
     where
         M: MapAccess<'de>,
     {
-        let mut map = BTreeMap::<String, ValueSubTree>::new();
+        let mut map = IndexMap::<String, ValueSubTree>::new();
 
         // While there are entries remaining in the input, add them
         // into our map.
@@ -112,7 +150,9 @@ impl fmt::Display for ValueSubTree {
     }
 }
 
+#[derive(Default)]
 pub enum CheckBytesValueRaw {
+    #[default]
     DefaultStar,
     Star,
     Equal(ValueSubTree),
@@ -128,12 +168,6 @@ impl CheckBytesValueRaw {
     }
 }
 
-impl Default for CheckBytesValueRaw {
-    fn default() -> Self {
-        CheckBytesValueRaw::DefaultStar
-    }
-}
-
 impl Serialize for CheckBytesValueRaw {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
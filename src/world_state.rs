@@ -0,0 +1,165 @@
+use super::account::*;
+use super::context::*;
+use super::error::*;
+use super::scenario::*;
+use super::value::*;
+use num_bigint::BigUint;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+
+/// State of a single account, as stored in the world state.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountState {
+    pub nonce: u64,
+    pub balance: BigUint,
+
+    /// Keys with an empty value are never stored.
+    pub storage: BTreeMap<Vec<u8>, Vec<u8>>,
+    pub code: Option<Vec<u8>>,
+
+    /// Not part of `setState`, only contract executors set it.
+    pub async_call_data: Vec<u8>,
+}
+
+/// Fails with `MandosError::FileNotLoaded` if the code is a lazily loaded file, see `AccountState::load`.
+impl TryFrom<&Account> for AccountState {
+    type Error = MandosError;
+
+    fn try_from(account: &Account) -> Result<Self, MandosError> {
+        let code = account.code.as_ref()
+            .map(|code| code.loaded().map(<[u8]>::to_vec))
+            .transpose()?;
+        Ok(AccountState::with_code(account, code))
+    }
+}
+
+impl AccountState {
+    /// Same as `AccountState::try_from`, but a lazily loaded code file is read, through `context`.
+    pub fn load(account: &Account, context: &InterpreterContext) -> Result<Self, MandosError> {
+        let code = account.code.as_ref()
+            .map(|code| code.load(context).map(Cow::into_owned))
+            .transpose()?;
+        Ok(AccountState::with_code(account, code))
+    }
+
+    fn with_code(account: &Account, code: Option<Vec<u8>>) -> Self {
+        AccountState {
+            nonce: account.nonce.value,
+            balance: account.balance.value.clone(),
+            storage: account.storage.iter()
+                .filter(|(_, value)| !value.value.is_empty())
+                .map(|(key, value)| (key.value.clone(), value.value.clone()))
+                .collect(),
+            code,
+            async_call_data: Vec::new(),
+        }
+    }
+
+    /// Missing keys read as empty, as in the VM.
+    pub fn storage_value(&self, key: &[u8]) -> &[u8] {
+        self.storage.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// Block info fields that are not specified default to 0.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockInfoState {
+    pub timestamp: u64,
+    pub nonce: u64,
+    pub round: u64,
+    pub epoch: u64,
+}
+
+impl From<&BlockInfo> for BlockInfoState {
+    fn from(block_info: &BlockInfo) -> Self {
+        let field = |value: &Option<U64Value>| value.as_ref().map_or(0, |v| v.value);
+        BlockInfoState {
+            timestamp: field(&block_info.block_timestamp),
+            nonce: field(&block_info.block_nonce),
+            round: field(&block_info.block_round),
+            epoch: field(&block_info.block_epoch),
+        }
+    }
+}
+
+/// Predicts the address of the contract deployed by `creator_address` with `creator_nonce`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NewAddressMock {
+    pub creator_address: [u8; 32],
+    pub creator_nonce: u64,
+    pub new_address: [u8; 32],
+}
+
+impl From<&NewAddress> for NewAddressMock {
+    fn from(new_address: &NewAddress) -> Self {
+        NewAddressMock {
+            creator_address: new_address.creator_address.value,
+            creator_nonce: new_address.creator_nonce.value,
+            new_address: new_address.new_address.value,
+        }
+    }
+}
+
+/// In-memory blockchain state that scenario steps are executed against.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorldState {
+    pub accounts: BTreeMap<[u8; 32], AccountState>,
+    pub new_addresses: Vec<NewAddressMock>,
+    pub block_hashes: Vec<Vec<u8>>,
+    pub previous_block_info: Option<BlockInfoState>,
+    pub current_block_info: Option<BlockInfoState>,
+}
+
+impl WorldState {
+    pub fn new() -> Self {
+        WorldState::default()
+    }
+
+    pub fn account(&self, address: &[u8; 32]) -> Option<&AccountState> {
+        self.accounts.get(address)
+    }
+
+    pub fn account_mut(&mut self, address: &[u8; 32]) -> Option<&mut AccountState> {
+        self.accounts.get_mut(address)
+    }
+
+    /// The address registered in `newAddresses` for a deploy by this creator at this nonce.
+    /// If several were registered, the latest wins.
+    pub fn new_address(&self, creator_address: &[u8; 32], creator_nonce: u64) -> Option<[u8; 32]> {
+        self.new_addresses.iter()
+            .rev()
+            .find(|mock| &mock.creator_address == creator_address && mock.creator_nonce == creator_nonce)
+            .map(|mock| mock.new_address)
+    }
+
+    /// Applies a `setState` step, with the same semantics as the Go test framework:
+    /// - listed accounts are replaced as a whole, all others are left untouched;
+    /// - new address predictions are appended;
+    /// - block hashes and both block infos are replaced, even when the step leaves them out.
+    ///
+    /// Returns false, without changing anything, if the step is not a `setState`.
+    /// Fails, without changing anything, if the code of an account is a lazily loaded file.
+    pub fn apply_set_state(&mut self, step: &Step) -> Result<bool, MandosError> {
+        if let Step::SetState {
+            accounts,
+            new_addresses,
+            block_hashes,
+            previous_block_info,
+            current_block_info,
+            ..
+        } = step {
+            let accounts = accounts.iter()
+                .map(|(address, account)| Ok((address.value, AccountState::try_from(account)?)))
+                .collect::<Result<Vec<_>, MandosError>>()?;
+            self.accounts.extend(accounts);
+            self.new_addresses.extend(new_addresses.iter().map(NewAddressMock::from));
+            self.block_hashes = block_hashes.iter().map(|hash| hash.value.clone()).collect();
+            self.previous_block_info = previous_block_info.as_ref().map(BlockInfoState::from);
+            self.current_block_info = current_block_info.as_ref().map(BlockInfoState::from);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
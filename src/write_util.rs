@@ -0,0 +1,22 @@
+use super::scenario_raw::*;
+
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Serializes a scenario in the canonical Mandos layout:
+/// fields in declaration order, 4-space indentation and a trailing newline.
+/// Unknown fields go back to the position they had in the file the scenario was parsed from.
+pub fn scenario_to_string(scenario: &ScenarioRaw) -> String {
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut ser = serde_json::Serializer::with_formatter(Vec::new(), formatter);
+    scenario.to_ordered_value().serialize(&mut ser).expect("scenario serialization cannot fail");
+    let mut serialized = String::from_utf8(ser.into_inner()).expect("serde_json produces valid UTF-8");
+    serialized.push('\n');
+    serialized
+}
+
+pub fn write_scenario<P: AsRef<Path>>(path: P, scenario: &ScenarioRaw) -> io::Result<()> {
+    fs::write(path, scenario_to_string(scenario))
+}
@@ -0,0 +1,121 @@
+
+extern crate mandos;
+use mandos::*;
+
+use std::path::Path;
+
+struct Repeat;
+
+impl ValuePrefixHandler for Repeat {
+    fn interpret(&self, value: &str, context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+        let once = interpret_string(value, context)?;
+        Ok([once.as_slice(), once.as_slice()].concat())
+    }
+}
+
+#[test]
+fn test_default_context() {
+    let context = InterpreterContext::default();
+    assert_eq!(Path::new(""), context.context_path());
+    assert_eq!(InterpreterMode::Strict, context.mode());
+    assert!(!context.lazy_file_loading());
+}
+
+#[test]
+fn test_in_memory_files() {
+    let context = InterpreterContext::builder()
+        .context_path("scenarios")
+        .file_loader(InMemoryFileLoader::new().with_file("scenarios/code.wasm", b"wasm"))
+        .build();
+
+    assert_eq!(b"wasm".to_vec(), interpret_string("file:code.wasm", &context).unwrap());
+    assert_eq!(
+        Err(MandosError::FileRead {
+            path: Path::new("scenarios/other.wasm").to_path_buf(),
+            error: "file not found in memory".to_string(),
+        }),
+        interpret_string("file:other.wasm", &context));
+}
+
+#[test]
+fn test_lenient_missing_file() {
+    let context = InterpreterContext::builder()
+        .file_loader(InMemoryFileLoader::new())
+        .mode(InterpreterMode::Lenient)
+        .build();
+
+    assert!(matches!(interpret_string("file:code.wasm", &context), Err(MandosError::FileRead { .. })));
+    assert!(context.warnings().is_empty());
+}
+
+#[test]
+fn test_prefix_handler() {
+    let context = InterpreterContext::builder()
+        .prefix_handler("twice:", Repeat)
+        .build();
+
+    assert_eq!(b"abab".to_vec(), interpret_string("twice:str:ab", &context).unwrap());
+    assert_eq!(b"xabab".to_vec(), interpret_string("str:x|twice:str:ab", &context).unwrap());
+}
+
+fn fixed_point_18(value: &str, _context: &InterpreterContext) -> Result<Vec<u8>, MandosError> {
+    let (int_part, frac_part) = match value.find('.') {
+        Some(dot) => (&value[..dot], &value[dot + 1..]),
+        None => (value, ""),
+    };
+    if frac_part.len() > 18 {
+        return Err(MandosError::Custom(format!("too many decimals: {}", value)));
+    }
+    let digits = format!("{}{:0<18}", int_part, frac_part);
+    let parsed = num_bigint::BigUint::parse_bytes(digits.as_bytes(), 10)
+        .ok_or_else(|| MandosError::InvalidDecimal(value.to_string()))?;
+    Ok(parsed.to_bytes_be())
+}
+
+#[test]
+fn test_closure_prefix_handler() {
+    let context = InterpreterContext::builder()
+        .prefix_handler("fp18:", fixed_point_18)
+        .prefix_handler("token:", |value: &str, _: &InterpreterContext| Ok(value.to_uppercase().into_bytes()))
+        .build();
+
+    assert_eq!(
+        interpret_string("1,500,000,000,000,000,000", &context).unwrap(),
+        interpret_string("fp18:1.5", &context).unwrap());
+    assert_eq!(b"WEGLD-ABCDEF".to_vec(), interpret_string("token:wegld-abcdef", &context).unwrap());
+    assert_eq!(
+        Err(MandosError::Custom("too many decimals: 0.0000000000000000001".to_string())),
+        interpret_string("fp18:0.0000000000000000001", &context));
+}
+
+#[test]
+fn test_boxed_prefix_handler() {
+    let handler: Box<dyn ValuePrefixHandler> = Box::new(Repeat);
+    let context = InterpreterContext::builder()
+        .prefix_handler("twice:", handler)
+        .build();
+
+    assert_eq!(b"abab".to_vec(), interpret_string("twice:str:ab", &context).unwrap());
+}
+
+#[test]
+fn test_prefix_handler_precedence() {
+    let context = InterpreterContext::builder()
+        .prefix_handler("u8:", |_: &str, _: &InterpreterContext| Ok(b"custom".to_vec()))
+        .build();
+
+    assert_eq!(b"custom".to_vec(), interpret_string("u8:1", &context).unwrap());
+    assert_eq!(vec![0, 1], interpret_string("u16:1", &context).unwrap());
+}
+
+#[test]
+fn test_unknown_prefix() {
+    let context = InterpreterContext::default();
+
+    assert_eq!(
+        Err(MandosError::UnknownPrefix { prefix: "fp18:".to_string(), value: "fp18:1.5".to_string() }),
+        interpret_string("fp18:1.5", &context));
+    assert_eq!(
+        "unknown value prefix `adress:` in adress:owner",
+        interpret_string("str:x|adress:owner", &context).unwrap_err().to_string());
+}
@@ -0,0 +1,91 @@
+
+extern crate mandos;
+use mandos::*;
+
+use std::path::{Path, PathBuf};
+
+fn dump_state_comment(step: &Step) -> &str {
+    match step {
+        Step::DumpState { comment, .. } => comment.as_deref().unwrap(),
+        other => panic!("dumpState step expected, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_resolve_external_steps() {
+    let scenario = resolve_scenario("tests/scenarios/external/main.scen.json");
+    assert_eq!(Some("main".to_string()), scenario.name);
+
+    let steps: Vec<(&str, &Path)> = scenario.steps.iter()
+        .map(|resolved| (dump_state_comment(&resolved.step), resolved.file.as_path()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("main 1", Path::new("tests/scenarios/external/main.scen.json")),
+            ("middle", Path::new("tests/scenarios/external/steps/middle.scen.json")),
+            ("leaf", Path::new("tests/scenarios/external/steps/leaf.scen.json")),
+            ("main 2", Path::new("tests/scenarios/external/main.scen.json")),
+        ],
+        steps);
+}
+
+#[test]
+fn test_include_cycle() {
+    let err = try_resolve_scenario("tests/scenarios/external/cycle_a.scen.json").unwrap_err();
+    match &err {
+        ParseScenarioError::IncludeCycle { chain } => assert_eq!(
+            &vec![
+                PathBuf::from("tests/scenarios/external/cycle_a.scen.json"),
+                PathBuf::from("tests/scenarios/external/steps/cycle_b.scen.json"),
+                PathBuf::from("tests/scenarios/external/steps/../cycle_a.scen.json"),
+            ],
+            chain),
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(
+        "externalSteps include cycle: tests/scenarios/external/cycle_a.scen.json -> tests/scenarios/external/steps/cycle_b.scen.json -> tests/scenarios/external/steps/../cycle_a.scen.json",
+        err.to_string());
+}
+
+#[test]
+fn test_missing_external_file() {
+    let err = try_resolve_scenario("tests/scenarios/external/missing_include.scen.json").unwrap_err();
+    match &err {
+        ParseScenarioError::Included { chain, error } => {
+            assert_eq!(
+                &vec![
+                    PathBuf::from("tests/scenarios/external/missing_include.scen.json"),
+                    PathBuf::from("tests/scenarios/external/steps/broken.scen.json"),
+                ],
+                chain);
+            assert!(
+                matches!(**error, ParseScenarioError::Io { ref path, .. } if path == Path::new("tests/scenarios/external/steps/does_not_exist.scen.json")),
+                "unexpected error: {:?}", error);
+        },
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert!(err.to_string().ends_with(
+        " (included from tests/scenarios/external/missing_include.scen.json -> tests/scenarios/external/steps/broken.scen.json)"));
+}
+
+#[test]
+fn test_strict_mode_in_included_file() {
+    let path = "tests/scenarios/external/unknown_fields_include.scen.json";
+    assert!(try_resolve_scenario(path).is_ok());
+
+    let err = try_resolve_scenario_with(path, |file| {
+        InterpreterContext::builder()
+            .scenario_file(file)
+            .unknown_field_policy(UnknownFieldPolicy::Reject)
+            .build()
+    }).unwrap_err();
+    match &err {
+        ParseScenarioError::Included { chain, error } => {
+            assert_eq!(&vec![PathBuf::from(path)], chain);
+            assert!(
+                matches!(**error, ParseScenarioError::UnknownField { ref field, .. } if field == "steps[0].accounts[\"address:owner\"].owner"),
+                "unexpected error: {:?}", error);
+        },
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
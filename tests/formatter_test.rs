@@ -0,0 +1,95 @@
+
+extern crate mandos;
+use mandos::*;
+
+const HINTS: [FormatHint; 6] = [
+    FormatHint::Auto,
+    FormatHint::Address,
+    FormatHint::Str,
+    FormatHint::Number,
+    FormatHint::U64,
+    FormatHint::Bool,
+];
+
+#[test]
+fn test_format_with_hint() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!("address:owner", format_value(&interpret_string("address:owner", context).unwrap(), FormatHint::Address));
+    assert_eq!("sc:adder", format_value(&interpret_string("sc:adder", context).unwrap(), FormatHint::Address));
+    assert_eq!("str:hello world", format_value(b"hello world", FormatHint::Str));
+    assert_eq!("str:", format_value(b"", FormatHint::Str));
+    assert_eq!("1000000", format_value(&[0x0f, 0x42, 0x40], FormatHint::Number));
+    assert_eq!("0", format_value(&[], FormatHint::Number));
+    assert_eq!("u64:256", format_value(&[0, 0, 0, 0, 0, 0, 1, 0], FormatHint::U64));
+    assert_eq!("true", format_value(&[1], FormatHint::Bool));
+    assert_eq!("false", format_value(&[], FormatHint::Bool));
+}
+
+#[test]
+fn test_format_sc_address_for_vm() {
+    let context = &InterpreterContext::builder()
+        .vm_type([5, 1])
+        .build();
+    let bytes = interpret_string("sc:adder", context).unwrap();
+
+    assert_eq!("sc:adder", format_value_for_vm(&bytes, FormatHint::Address, context.vm_type()));
+    assert_eq!(bytes, interpret_string(&format_value(&bytes, FormatHint::Address), context).unwrap());
+    assert!(format_value(&bytes, FormatHint::Address).starts_with("0x"));
+}
+
+#[test]
+fn test_format_fallback_to_hex() {
+    assert_eq!("0x0102", format_value(&[1, 2], FormatHint::Address));
+    assert_eq!("0xff00", format_value(&[0xff, 0x00], FormatHint::Str));
+    assert_eq!("0x0005", format_value(&[0x00, 0x05], FormatHint::Number));
+    assert_eq!("0x02", format_value(&[2], FormatHint::Bool));
+    assert_eq!("0x7c", format_value(b"|", FormatHint::Str));
+}
+
+#[test]
+fn test_format_auto() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!("", format_value(&[], FormatHint::Auto));
+    assert_eq!("address:owner", format_value(&interpret_string("address:owner", context).unwrap(), FormatHint::Auto));
+    assert_eq!("sc:adder", format_value(&interpret_string("sc:adder", context).unwrap(), FormatHint::Auto));
+    assert_eq!("str:EGLD-123456", format_value(b"EGLD-123456", FormatHint::Auto));
+    assert_eq!("1000", format_value(&[0x03, 0xe8], FormatHint::Auto));
+    assert_eq!(
+        "0x0102030405060708090a",
+        format_value(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10], FormatHint::Auto));
+}
+
+#[test]
+fn test_format_round_trip() {
+    let context = &InterpreterContext::default();
+    let samples: Vec<Vec<u8>> = vec![
+        Vec::new(),
+        vec![0],
+        vec![1],
+        vec![0, 0, 1],
+        vec![0xff; 8],
+        vec![0x00; 8],
+        vec![b'_'; 32],
+        vec![0; 32],
+        b"a|b".to_vec(),
+        b"with\nnewline".to_vec(),
+        "ünïcödé".as_bytes().to_vec(),
+        interpret_string("address:a", context).unwrap(),
+        interpret_string("sc:a_", context).unwrap(),
+        interpret_string("sc:", context).unwrap(),
+        interpret_string("keccak256:str:abc", context).unwrap(),
+        interpret_string("u64:12345", context).unwrap(),
+        interpret_string("-1", context).unwrap(),
+    ];
+    for bytes in samples.iter() {
+        for hint in HINTS.iter() {
+            let formatted = format_value(bytes, *hint);
+            assert_eq!(
+                *bytes,
+                interpret_string(&formatted, context).unwrap(),
+                "{:?} formatted as {:?} gave {}", bytes, hint, formatted);
+        }
+    }
+}
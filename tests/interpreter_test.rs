@@ -2,125 +2,461 @@
 extern crate mandos;
 use mandos::*;
 
+use bech32::ToBase32;
+
 const EMPTY: Vec<u8> = Vec::<u8>::new();
 
 #[test]
 fn test_bool() {
     let context = &InterpreterContext::default();
-    assert_eq!(vec![1], interpret_string("true", context));
-    assert_eq!(EMPTY, interpret_string("false", context));
+    assert_eq!(vec![1], interpret_string("true", context).unwrap());
+    assert_eq!(EMPTY, interpret_string("false", context).unwrap());
 }
 
 #[test]
 fn test_string() {
     let context = &InterpreterContext::default();
 
-    assert_eq!(b"abcdefg".to_vec(), interpret_string("``abcdefg", context));
-    assert_eq!(EMPTY, interpret_string("``", context));
-    assert_eq!(b"`".to_vec(), interpret_string("```", context));
-    assert_eq!(b" ".to_vec(), interpret_string("`` ", context));
+    assert_eq!(b"abcdefg".to_vec(), interpret_string("``abcdefg", context).unwrap());
+    assert_eq!(EMPTY, interpret_string("``", context).unwrap());
+    assert_eq!(b"`".to_vec(), interpret_string("```", context).unwrap());
+    assert_eq!(b" ".to_vec(), interpret_string("`` ", context).unwrap());
 
-    assert_eq!(b"abcdefg".to_vec(), interpret_string("''abcdefg", context));
-    assert_eq!(EMPTY, interpret_string("''", context));
-    assert_eq!(b"'".to_vec(), interpret_string("'''", context));
-    assert_eq!(b"``".to_vec(), interpret_string("''``", context));
+    assert_eq!(b"abcdefg".to_vec(), interpret_string("''abcdefg", context).unwrap());
+    assert_eq!(EMPTY, interpret_string("''", context).unwrap());
+    assert_eq!(b"'".to_vec(), interpret_string("'''", context).unwrap());
+    assert_eq!(b"``".to_vec(), interpret_string("''``", context).unwrap());
     
-    assert_eq!(b"abcdefg".to_vec(), interpret_string("str:abcdefg", context));
-    assert_eq!(EMPTY, interpret_string("str:", context));
+    assert_eq!(b"abcdefg".to_vec(), interpret_string("str:abcdefg", context).unwrap());
+    assert_eq!(EMPTY, interpret_string("str:", context).unwrap());
 }
 
 #[test]
 fn test_address() {
     let context = &InterpreterContext::default();
 
-    assert_eq!(b"________________________________".to_vec(), interpret_string("address:", context));
-    assert_eq!(b"a_______________________________".to_vec(), interpret_string("address:a", context));
-    assert_eq!(b"an_address______________________".to_vec(), interpret_string("address:an_address", context));
-    assert_eq!(b"12345678901234567890123456789012".to_vec(), interpret_string("address:12345678901234567890123456789012", context));
-    assert_eq!(b"12345678901234567890123456789012".to_vec(), interpret_string("address:123456789012345678901234567890123", context));
+    assert_eq!(b"________________________________".to_vec(), interpret_string("address:", context).unwrap());
+    assert_eq!(b"a_______________________________".to_vec(), interpret_string("address:a", context).unwrap());
+    assert_eq!(b"an_address______________________".to_vec(), interpret_string("address:an_address", context).unwrap());
+    assert_eq!(b"12345678901234567890123456789012".to_vec(), interpret_string("address:12345678901234567890123456789012", context).unwrap());
+    assert_eq!(b"12345678901234567890123456789012".to_vec(), interpret_string("address:123456789012345678901234567890123", context).unwrap());
+}
+
+#[test]
+fn test_sc_address() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(b"\x00\x00\x00\x00\x00\x00\x00\x00\x05\x00______________________".to_vec(), interpret_string("sc:", context).unwrap());
+    assert_eq!(b"\x00\x00\x00\x00\x00\x00\x00\x00\x05\x00a_____________________".to_vec(), interpret_string("sc:a", context).unwrap());
+    assert_eq!(b"\x00\x00\x00\x00\x00\x00\x00\x00\x05\x00an_address____________".to_vec(), interpret_string("sc:an_address", context).unwrap());
+    assert_eq!(b"\x00\x00\x00\x00\x00\x00\x00\x00\x05\x001234567890123456789012".to_vec(), interpret_string("sc:1234567890123456789012", context).unwrap());
+    assert_eq!(b"\x00\x00\x00\x00\x00\x00\x00\x00\x05\x001234567890123456789012".to_vec(), interpret_string("sc:12345678901234567890123", context).unwrap());
+
+    let context = &InterpreterContext::builder().vm_type([7, 1]).build();
+    assert_eq!(b"\x00\x00\x00\x00\x00\x00\x00\x00\x07\x01a_____________________".to_vec(), interpret_string("sc:a", context).unwrap());
+}
+
+#[test]
+fn test_sc_address_key_and_value() {
+    let context = &InterpreterContext::default();
+    let expected = *b"\x00\x00\x00\x00\x00\x00\x00\x00\x05\x00adder_________________";
+
+    assert_eq!(expected, AddressKey::interpret_from("sc:adder".to_string(), context).unwrap().value);
+    assert_eq!(expected, AddressValue::interpret_from(ValueSubTree::from("sc:adder"), context).unwrap().value);
 }
 
 #[test]
 fn test_unsigned_number() {
     let context = &InterpreterContext::default();
 
-    assert_eq!(vec![0x12, 0x34], interpret_string("0x1234", context));
-    assert_eq!(vec![0x00], interpret_string("0x0", context));
-    assert_eq!(vec![0x00], interpret_string("0x00", context));
-    assert_eq!(vec![0x00, 0x00], interpret_string("0x000", context));
-    assert_eq!(vec![0x00, 0x00], interpret_string("0x0000", context));
-    assert_eq!(vec![0x00, 0x00, 0xab], interpret_string("0x0000ab", context));
-    assert_eq!(EMPTY, interpret_string("0x", context));
-    assert_eq!(EMPTY, interpret_string("0", context));
-    assert_eq!(vec![12], interpret_string("12", context));
-    assert_eq!(vec![0x01, 0x00], interpret_string("256", context));
-    assert_eq!(vec![0x01], interpret_string("0b1", context));
-    assert_eq!(vec![0x05], interpret_string("0b101", context));
+    assert_eq!(vec![0x12, 0x34], interpret_string("0x1234", context).unwrap());
+    assert_eq!(vec![0x00], interpret_string("0x0", context).unwrap());
+    assert_eq!(vec![0x00], interpret_string("0x00", context).unwrap());
+    assert_eq!(vec![0x00, 0x00], interpret_string("0x000", context).unwrap());
+    assert_eq!(vec![0x00, 0x00], interpret_string("0x0000", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0xab], interpret_string("0x0000ab", context).unwrap());
+    assert_eq!(EMPTY, interpret_string("0x", context).unwrap());
+    assert_eq!(EMPTY, interpret_string("0", context).unwrap());
+    assert_eq!(vec![12], interpret_string("12", context).unwrap());
+    assert_eq!(vec![0x01, 0x00], interpret_string("256", context).unwrap());
+    assert_eq!(vec![0x01], interpret_string("0b1", context).unwrap());
+    assert_eq!(vec![0x05], interpret_string("0b101", context).unwrap());
 }
 
 #[test]
 fn test_signed_number() {
     let context = &InterpreterContext::default();
 
-    assert_eq!(vec![0xff], interpret_string("-1", context));
-    assert_eq!(vec![0xff], interpret_string("255", context));
-    assert_eq!(vec![0xff], interpret_string("0xff", context));
-    assert_eq!(vec![0x00, 0xff], interpret_string("+255", context));
-    assert_eq!(vec![0x00, 0xff], interpret_string("+0xff", context));
+    assert_eq!(vec![0xff], interpret_string("-1", context).unwrap());
+    assert_eq!(vec![0xff], interpret_string("255", context).unwrap());
+    assert_eq!(vec![0xff], interpret_string("0xff", context).unwrap());
+    assert_eq!(vec![0x00, 0xff], interpret_string("+255", context).unwrap());
+    assert_eq!(vec![0x00, 0xff], interpret_string("+0xff", context).unwrap());
 
-    assert_eq!(vec![0xff, 0x00], interpret_string("-256", context));
-    assert_eq!(vec![0xfb], interpret_string("-0b101", context));
+    assert_eq!(vec![0xff, 0x00], interpret_string("-256", context).unwrap());
+    assert_eq!(vec![0xfb], interpret_string("-0b101", context).unwrap());
 }
 
 #[test]
 fn test_unsigned_fixed_width() {
     let context = &InterpreterContext::default();
 
-	assert_eq!(vec![0x00], interpret_string("u8:0", context));
-	assert_eq!(vec![0x00, 0x00], interpret_string("u16:0", context));
-	assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("u32:0", context));
-	assert_eq!(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], interpret_string("u64:0", context));
-	assert_eq!(vec![0x12, 0x34], interpret_string("u16:0x1234", context));
-	assert_eq!(vec![0x00, 0x00, 0x12, 0x34], interpret_string("u32:0x1234", context));
-	assert_eq!(vec![0x01, 0x00], interpret_string("u16:256", context));
-	assert_eq!(vec![0x01], interpret_string("u8:0b1", context));
-	assert_eq!(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05], interpret_string("u64:0b101", context));
+	assert_eq!(vec![0x00], interpret_string("u8:0", context).unwrap());
+	assert_eq!(vec![0x00, 0x00], interpret_string("u16:0", context).unwrap());
+	assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("u32:0", context).unwrap());
+	assert_eq!(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], interpret_string("u64:0", context).unwrap());
+	assert_eq!(vec![0x12, 0x34], interpret_string("u16:0x1234", context).unwrap());
+	assert_eq!(vec![0x00, 0x00, 0x12, 0x34], interpret_string("u32:0x1234", context).unwrap());
+	assert_eq!(vec![0x01, 0x00], interpret_string("u16:256", context).unwrap());
+	assert_eq!(vec![0x01], interpret_string("u8:0b1", context).unwrap());
+	assert_eq!(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05], interpret_string("u64:0b101", context).unwrap());
 }
 
 #[test]
 fn test_signed_fixed_width() {
     let context = &InterpreterContext::default();
 
-	assert_eq!(vec![0x00], interpret_string("i8:0", context));
-	assert_eq!(vec![0x00, 0x00], interpret_string("i16:0", context));
-	assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("i32:0", context));
-	assert_eq!(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], interpret_string("i64:0", context));
+	assert_eq!(vec![0x00], interpret_string("i8:0", context).unwrap());
+	assert_eq!(vec![0x00, 0x00], interpret_string("i16:0", context).unwrap());
+	assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("i32:0", context).unwrap());
+	assert_eq!(vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], interpret_string("i64:0", context).unwrap());
+
+	assert_eq!(vec![0xff], interpret_string("i8:-1", context).unwrap());
+	assert_eq!(vec![0xff, 0xff], interpret_string("i16:-1", context).unwrap());
+	assert_eq!(vec![0xff, 0xff, 0xff, 0xff], interpret_string("i32:-1", context).unwrap());
+	assert_eq!(vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], interpret_string("i64:-1", context).unwrap());
+	assert_eq!(vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00], interpret_string("i64:-256", context).unwrap());
+	assert_eq!(vec![0xfb], interpret_string("i8:-0b101", context).unwrap());
+}
+
+#[test]
+fn test_signed_fixed_width_overflow() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(
+        Err(MandosError::FixedWidthOverflow { value: "255".to_string(), width: 1 }),
+        interpret_string("i8:+255", context));
+    assert_eq!(
+        Err(MandosError::FixedWidthOverflow { value: "0xff".to_string(), width: 1 }),
+        interpret_string("i8:0xff", context));
+    assert_eq!(
+        Err(MandosError::FixedWidthOverflow { value: "-255".to_string(), width: 1 }),
+        interpret_string("i8:-255", context));
+}
+
+#[test]
+fn test_unsigned_fixed_width_overflow() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(
+        Err(MandosError::FixedWidthOverflow { value: "256".to_string(), width: 1 }),
+        interpret_string("u8:256", context));
+    assert_eq!(
+        Err(MandosError::FixedWidthOverflow { value: "0x123456".to_string(), width: 2 }),
+        interpret_string("u16:0x123456", context));
+}
+
+#[test]
+fn test_invalid_number() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(Err(MandosError::InvalidHex("0xzz".to_string())), interpret_string("0xzz", context));
+    assert_eq!(Err(MandosError::InvalidBinary("0b102".to_string())), interpret_string("0b102", context));
+    assert_eq!(Err(MandosError::InvalidDecimal("12a".to_string())), interpret_string("12a", context));
+    assert_eq!(Err(MandosError::InvalidDecimal("abc".to_string())), interpret_string("str:x|abc", context));
+}
+
+#[test]
+fn test_u64_overflow() {
+    let context = &InterpreterContext::default();
+
+    let vst = ValueSubTree::from("0x010000000000000000");
+    assert_eq!(
+        MandosError::IntegerOverflow { value: "\"0x010000000000000000\"".to_string(), type_name: "u64" },
+        U64Value::interpret_from(vst, context).unwrap_err());
+}
+
+#[test]
+fn test_keccak256() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(
+        hex::decode("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470").unwrap(),
+        interpret_string("keccak256:", context).unwrap());
+    assert_eq!(
+        hex::decode("4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45").unwrap(),
+        interpret_string("keccak256:str:abc", context).unwrap());
+    assert_eq!(
+        hex::decode("290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563").unwrap(),
+        interpret_string("keccak256:u64:0|u64:0|u64:0|u64:0", context).unwrap());
+    assert_eq!(
+        hex::decode("b10e2d527612073b26eecdfd717e6a320cf44b4afac2b0732d9fcbe2b7fa0cf6").unwrap(),
+        interpret_string("keccak256:0x0000000000000000000000000000000000000000000000000000000000000001", context).unwrap());
+    assert_eq!(
+        hex::decode("ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5").unwrap(),
+        interpret_string("keccak256:0x0000000000000000000000000000000000000000000000000000000000000000|0x0000000000000000000000000000000000000000000000000000000000000000", context).unwrap());
+}
+
+#[test]
+fn test_keccak256_nested() {
+    let context = &InterpreterContext::default();
+
+    let abc_hash = interpret_string("keccak256:str:abc", context).unwrap();
+    assert_eq!(abc_hash, interpret_string("keccak256:str:a|str:b|``c", context).unwrap());
+
+    let mut concat = b"prefix".to_vec();
+    concat.extend_from_slice(abc_hash.as_slice());
+    assert_eq!(concat, interpret_string("str:prefix|keccak256:str:abc", context).unwrap());
+
+    assert_eq!(
+        interpret_string(&format!("keccak256:0x{}", hex::encode(&abc_hash)), context).unwrap(),
+        interpret_string("keccak256:keccak256:str:abc", context).unwrap());
+
+    assert_eq!(Err(MandosError::InvalidDecimal("xyz".to_string())), interpret_string("keccak256:xyz", context));
+}
+
+#[test]
+fn test_nested() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("nested:", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("nested:str:", context).unwrap());
+    assert_eq!(b"\x00\x00\x00\x03abc".to_vec(), interpret_string("nested:str:abc", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x02, 0x12, 0x34], interpret_string("nested:0x1234", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x05], interpret_string("nested:u32:5", context).unwrap());
+    assert_eq!(
+        b"\x00\x00\x00\x03abc\x00\x00\x00\x05\x00\x00\x00\x02xy".to_vec(),
+        interpret_string("nested:str:abc|u32:5|nested:str:xy", context).unwrap());
+    assert_eq!(
+        b"\x00\x00\x00\x06\x00\x00\x00\x02xy".to_vec(),
+        interpret_string("nested:nested:str:xy", context).unwrap());
+}
+
+#[test]
+fn test_biguint() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x00], interpret_string("biguint:0", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x01, 0x05], interpret_string("biguint:5", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x02, 0x01, 0x00], interpret_string("biguint:256", context).unwrap());
+    assert_eq!(vec![0x00, 0x00, 0x00, 0x01, 0x01], interpret_string("biguint:0x000001", context).unwrap());
+    assert_eq!(
+        vec![0x00, 0x00, 0x00, 0x01, 0x07, 0x00, 0x00, 0x00, 0x00],
+        interpret_string("biguint:7|biguint:0", context).unwrap());
+
+    assert_eq!(
+        Err(MandosError::SignedBigUint("biguint:-1".to_string())),
+        interpret_string("biguint:-1", context));
+    assert_eq!(
+        Err(MandosError::SignedBigUint("biguint:i8:5".to_string())),
+        interpret_string("biguint:i8:5", context));
+}
+
+const ALICE_BECH32: &str = "erd1qyu5wthldzr8wx5c9ucg8kjagg0jfs53s8nr3zpz3hypefsdd8ssycr6th";
+const ALICE_HEX: &str = "0139472eff6886771a982f3083da5d421f24c29181e63888228dc81ca60d69e1";
+
+#[test]
+fn test_bech32() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(hex::decode(ALICE_HEX).unwrap(), interpret_string(&format!("bech32:{}", ALICE_BECH32), context).unwrap());
+
+    let mut alice = [0u8; 32];
+    alice.copy_from_slice(hex::decode(ALICE_HEX).unwrap().as_slice());
+    assert_eq!(ALICE_BECH32, bech32_encode(&alice));
+    assert_eq!(alice, bech32_decode(ALICE_BECH32).unwrap());
+}
+
+#[test]
+fn test_bech32_invalid() {
+    let context = &InterpreterContext::default();
+
+    // last character changed, checksum fails
+    assert!(matches!(
+        interpret_string("bech32:erd1qyu5wthldzr8wx5c9ucg8kjagg0jfs53s8nr3zpz3hypefsdd8ssycr6tt", context),
+        Err(MandosError::InvalidBech32 { .. })));
+    assert!(matches!(
+        bech32_decode(&bech32::encode("abc", [0u8; 32].to_base32(), bech32::Variant::Bech32).unwrap()),
+        Err(MandosError::InvalidBech32 { ref reason, .. }) if reason == "expected prefix `erd`, found `abc`"));
+    assert!(matches!(
+        bech32_decode(&bech32::encode("erd", [0u8; 20].to_base32(), bech32::Variant::Bech32).unwrap()),
+        Err(MandosError::InvalidBech32 { ref reason, .. }) if reason == "expected 32 bytes, found 20"));
+}
+
+#[test]
+fn test_bech32_address_positions() {
+    let context = &InterpreterContext::default();
+    let alice = hex::decode(ALICE_HEX).unwrap();
+
+    let key = AddressKey::interpret_from(ALICE_BECH32.to_string(), context).unwrap();
+    assert_eq!(alice, key.value.to_vec());
+    assert_eq!(ALICE_BECH32, key.to_bech32());
+
+    let value = AddressValue::interpret_from(ValueSubTree::from(ALICE_BECH32), context).unwrap();
+    assert_eq!(alice, value.value.to_vec());
+    assert_eq!(ALICE_BECH32, value.to_bech32());
+
+    // bare bech32 is only an address, not a general value
+    assert!(interpret_string(ALICE_BECH32, context).is_err());
+}
+
+#[test]
+fn test_address_length_strict() {
+    let context = &InterpreterContext::default();
+
+    assert_eq!(
+        Err(MandosError::InvalidAddressLength {
+            value: "``a_31_byte_address______________".to_string(),
+            length: 31,
+        }),
+        AddressKey::interpret_from("``a_31_byte_address______________".to_string(), context));
+    assert_eq!(
+        Err(MandosError::InvalidAddressLength {
+            value: "\"0x1234\"".to_string(),
+            length: 2,
+        }),
+        AddressValue::interpret_from(ValueSubTree::from("0x1234"), context));
+}
+
+#[test]
+fn test_address_length_lenient() {
+    let context = &InterpreterContext::builder()
+        .mode(InterpreterMode::Lenient)
+        .build();
+
+    let key = AddressKey::interpret_from("``a_31_byte_address______________".to_string(), context).unwrap();
+    assert_eq!(b"a_31_byte_address______________\0"[..], key.value[..]);
+    let value = AddressValue::interpret_from(ValueSubTree::from("0x1234"), context).unwrap();
+    let mut expected = [0u8; 32];
+    expected[.. 2].copy_from_slice(&[0x12, 0x34]);
+    assert_eq!(expected, value.value);
+
+    let warnings = context.take_warnings();
+    assert_eq!(2, warnings.len());
+    assert!(matches!(warnings[0], MandosError::InvalidAddressLength { length: 31, .. }));
+    assert!(matches!(warnings[1], MandosError::InvalidAddressLength { length: 2, .. }));
+}
+
+#[test]
+fn test_address_key_eq() {
+    let context = &InterpreterContext::default();
+
+    let key1 = AddressKey::interpret_from("address:a".to_string(), context).unwrap();
+    let key2 = AddressKey::interpret_from("0x615f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f".to_string(), context).unwrap();
+    let key3 = AddressKey::interpret_from("address:b".to_string(), context).unwrap();
+    assert_eq!(key1, key2);
+    assert_ne!(key1, key3);
+    assert!(key1 < key3);
+    assert_eq!("address:a", key1.to_string());
+}
+
+#[test]
+fn test_duplicate_address_keys() {
+    let context = &InterpreterContext::default();
+    let raw: CheckAccountsRaw = serde_json::from_str(r#"{
+        "address:a": { "storage": "*" },
+        "0x615f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f": { "storage": "*" }
+    }"#).unwrap();
 
-	assert_eq!(vec![0xff], interpret_string("i8:-1", context));
-	assert_eq!(vec![0xff, 0xff], interpret_string("i16:-1", context));
-	assert_eq!(vec![0xff, 0xff, 0xff, 0xff], interpret_string("i32:-1", context));
-	assert_eq!(vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff], interpret_string("i64:-1", context));
-	assert_eq!(vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00], interpret_string("i64:-256", context));
-	assert_eq!(vec![0xfb], interpret_string("i8:-0b101", context));
+    let err = CheckAccounts::interpret_from(raw, context).unwrap_err();
+    assert_eq!(
+        MandosError::DuplicateKey {
+            first: "address:a".to_string(),
+            second: "0x615f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f5f".to_string(),
+        },
+        err);
 }
 
 #[test]
-#[should_panic]
-fn test_signed_fixed_width_panic_1() {
+fn test_malformed_address_keys_lenient() {
+    let context = &InterpreterContext::builder()
+        .mode(InterpreterMode::Lenient)
+        .build();
+    let raw: CheckAccountsRaw = serde_json::from_str(r#"{
+        "0x1234": { "storage": "*" },
+        "0x5678": { "storage": "*" }
+    }"#).unwrap();
+
+    let accounts = CheckAccounts::interpret_from(raw, context).unwrap();
+    assert_eq!(2, accounts.accounts.len());
+    assert_eq!(2, context.take_warnings().len());
+}
+
+#[test]
+fn test_duplicate_storage_keys() {
     let context = &InterpreterContext::default();
-    interpret_string("i8:+255", context);
+    let raw: AccountRaw = serde_json::from_str(r#"{
+        "nonce": "0",
+        "balance": "0",
+        "storage": {
+            "str:key": "1",
+            "0x6b6579": "2"
+        }
+    }"#).unwrap();
+
+    assert!(matches!(Account::interpret_from(raw, context), Err(MandosError::DuplicateKey { .. })));
 }
 
 #[test]
-#[should_panic]
-fn test_signed_fixed_width_panic_2() {
+fn test_typed_numbers() {
     let context = &InterpreterContext::default();
-    interpret_string("i8:0xff", context);
+
+    assert_eq!(0x12345678, U32Value::interpret_from(ValueSubTree::from("0x12345678"), context).unwrap().value);
+    assert_eq!(255, U8Value::interpret_from(ValueSubTree::from("255"), context).unwrap().value);
+    assert_eq!(-1, I64Value::interpret_from(ValueSubTree::from("-1"), context).unwrap().value);
+    assert_eq!(-1, I64Value::interpret_from(ValueSubTree::from("i64:-1"), context).unwrap().value);
+    assert_eq!(1000, I64Value::interpret_from(ValueSubTree::from("+1000"), context).unwrap().value);
+    assert_eq!(0, I64Value::interpret_from(ValueSubTree::from("0"), context).unwrap().value);
+
+    assert_eq!(
+        num_bigint::BigInt::from(-256),
+        BigIntValue::interpret_from(ValueSubTree::from("-256"), context).unwrap().value);
+    assert_eq!(
+        num_bigint::BigInt::from(255),
+        BigIntValue::interpret_from(ValueSubTree::from("+255"), context).unwrap().value);
+    assert_eq!(
+        num_bigint::BigInt::from(255),
+        BigIntValue::interpret_from(ValueSubTree::from("255"), context).unwrap().value);
+    assert_eq!(
+        num_bigint::BigInt::from(-1),
+        BigIntValue::interpret_from(ValueSubTree::from("0xff"), context).unwrap().value);
+    assert_eq!(255, I64Value::interpret_from(ValueSubTree::from("255"), context).unwrap().value);
+    assert_eq!(
+        num_bigint::BigInt::from(255),
+        BigIntValue::interpret_from(ValueSubTree::from("u8:255"), context).unwrap().value);
+    // the 4 byte length prefix of biguint: is part of the bytes
+    assert_eq!(
+        num_bigint::BigInt::from(0x0102),
+        BigIntValue::interpret_from(ValueSubTree::from("biguint:2"), context).unwrap().value);
+    assert_eq!(255, I64Value::interpret_from(ValueSubTree::from("u16:255"), context).unwrap().value);
+
+    assert_eq!(
+        MandosError::IntegerOverflow { value: "\"256\"".to_string(), type_name: "u8" },
+        U8Value::interpret_from(ValueSubTree::from("256"), context).unwrap_err());
+    assert_eq!(
+        MandosError::IntegerOverflow { value: "\"0x0100000000\"".to_string(), type_name: "u32" },
+        U32Value::interpret_from(ValueSubTree::from("0x0100000000"), context).unwrap_err());
+    assert_eq!(
+        MandosError::IntegerOverflow { value: "\"+0x8000000000000000\"".to_string(), type_name: "i64" },
+        I64Value::interpret_from(ValueSubTree::from("+0x8000000000000000"), context).unwrap_err());
+    assert_eq!(
+        MandosError::IntegerOverflow { value: "\"9223372036854775808\"".to_string(), type_name: "i64" },
+        I64Value::interpret_from(ValueSubTree::from("9223372036854775808"), context).unwrap_err());
+    assert_eq!(
+        MandosError::IntegerOverflow { value: "\"u64:18446744073709551615\"".to_string(), type_name: "i64" },
+        I64Value::interpret_from(ValueSubTree::from("u64:18446744073709551615"), context).unwrap_err());
 }
 
 #[test]
-#[should_panic]
-fn test_signed_fixed_width_panic_3() {
+fn test_bool_value() {
     let context = &InterpreterContext::default();
-    interpret_string("i8:-255", context);
+
+    assert!(BoolValue::interpret_from(ValueSubTree::from("true"), context).unwrap().value);
+    assert!(!BoolValue::interpret_from(ValueSubTree::from("false"), context).unwrap().value);
+    assert!(BoolValue::interpret_from(ValueSubTree::from("1"), context).unwrap().value);
+    assert!(!BoolValue::interpret_from(ValueSubTree::from(""), context).unwrap().value);
+    assert_eq!(
+        MandosError::InvalidBool("\"2\"".to_string()),
+        BoolValue::interpret_from(ValueSubTree::from("2"), context).unwrap_err());
+    assert_eq!("\"true\"", BoolValue::interpret_from(ValueSubTree::from("true"), context).unwrap().to_string());
 }
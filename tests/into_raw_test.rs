@@ -0,0 +1,103 @@
+
+extern crate mandos;
+use mandos::*;
+
+#[test]
+fn test_example_round_trip() {
+    let expected = serde_json::to_value(parse_scenario_raw("./example.scen.json")).unwrap();
+
+    let context = InterpreterContext::builder()
+        .scenario_file("./example.scen.json")
+        .lazy_file_loading(true)
+        .build();
+    let scenario = Scenario::interpret_from(parse_scenario_raw("./example.scen.json"), &context).unwrap();
+    let raw = scenario.into_raw();
+
+    assert_eq!(expected, serde_json::to_value(raw).unwrap());
+}
+
+#[test]
+fn test_built_scenario_into_raw() {
+    let context = &InterpreterContext::default();
+    let mut to = [0u8; 32];
+    to.copy_from_slice(&interpret_string("sc:adder", context).unwrap());
+
+    let scenario = Scenario {
+        name: None,
+        comment: None,
+        check_gas: None,
+        steps: vec![
+            Step::ScCall {
+                tx_id: "1".to_string(),
+                comment: None,
+                tx: TxCall {
+                    from: AddressValue::from([1u8; 32]),
+                    to: AddressValue::from(to),
+                    call_value: BigUintValue::from(0u64),
+                    function: "add".to_string(),
+                    arguments: vec![BytesValue::from(vec![5]), BytesValue::from("x")],
+                    gas_limit: U64Value::from(100_000),
+                    gas_price: U64Value::from(0),
+                    unknown_fields: UnknownFields::new(),
+                },
+                expect: Some(TxExpect {
+                    out: vec![CheckValue::Star],
+                    status: U64Value::from(0),
+                    logs: CheckLogs::DefaultStar,
+                    message: None,
+                    gas: Some(CheckValue::Star),
+                    refund: None,
+                    unknown_fields: UnknownFields::new(),
+                }),
+                unknown_fields: UnknownFields::new(),
+            },
+        ],
+        unknown_fields: UnknownFields::new(),
+    };
+
+    let serialized = serde_json::to_value(scenario.into_raw()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "steps": [{
+                "step": "scCall",
+                "txId": "1",
+                "tx": {
+                    "from": format!("0x{}", "01".repeat(32)),
+                    "to": "sc:adder",
+                    "value": "0",
+                    "function": "add",
+                    "arguments": ["5", "str:x"],
+                    "gasLimit": "100000",
+                    "gasPrice": "0",
+                },
+                "expect": {
+                    "out": ["*"],
+                    "status": "0",
+                    "gas": "*",
+                },
+            }],
+        }),
+        serialized);
+}
+
+#[test]
+fn test_empty_original_falls_back_to_canonical() {
+    let value = BytesValue {
+        value: vec![1, 2],
+        lazy_file: None,
+        original: ValueSubTree::default(),
+    };
+    assert_eq!(ValueSubTree::from("258"), value.into_raw());
+
+    let value = U64Value {
+        value: 0,
+        original: ValueSubTree::default(),
+    };
+    assert_eq!(ValueSubTree::default(), value.into_raw());
+
+    let key = BytesKey {
+        value: b"abc".to_vec(),
+        original: String::new(),
+    };
+    assert_eq!("str:abc", key.into_raw());
+}
@@ -0,0 +1,122 @@
+
+extern crate mandos;
+use mandos::*;
+
+use std::path::Path;
+
+#[test]
+fn test_parse_example() {
+    // the contract code referenced by the example is not in the repository
+    let raw = try_parse_scenario_raw("./example.scen.json").unwrap();
+    let context = InterpreterContext::builder()
+        .scenario_file("./example.scen.json")
+        .lazy_file_loading(true)
+        .build();
+    let scenario = Scenario::interpret_from(raw, &context).unwrap();
+    assert_eq!(Some("example scenario file".to_string()), scenario.name);
+}
+
+#[test]
+fn test_missing_file() {
+    let err = try_parse_scenario("./example.scen.json").map(|_| ()).unwrap_err();
+    match err {
+        ParseScenarioError::Interpret(MandosError::Located { location, error }) => {
+            assert_eq!(40, location.line);
+            assert!(
+                matches!(*error, MandosError::FileRead { ref path, .. } if path == Path::new("./smart-contract.wasm")),
+                "unexpected error: {:?}", error);
+        },
+        other => panic!("unexpected error: {:?}", other),
+    }
+}
+
+#[test]
+fn test_file_relative_to_scenario() {
+    let scenario = try_parse_scenario("./tests/scenarios/deploy.scen.json").unwrap();
+    if let Step::ScDeploy { tx, .. } = &scenario.steps[0] {
+        assert_eq!(b"\0asm\x01\0\0\0".to_vec(), tx.contract_code.value);
+    } else {
+        panic!("scDeploy step expected");
+    }
+}
+
+#[test]
+fn test_lazy_file_loading() {
+    let raw = try_parse_scenario_raw("./tests/scenarios/deploy.scen.json").unwrap();
+    let context = InterpreterContext::builder()
+        .scenario_file("./tests/scenarios/deploy.scen.json")
+        .lazy_file_loading(true)
+        .build();
+    let scenario = Scenario::interpret_from(raw, &context).unwrap();
+    if let Step::ScDeploy { tx, .. } = &scenario.steps[0] {
+        assert_eq!(Some(Path::new("./tests/scenarios/fake-contract.wasm")), tx.contract_code.lazy_file.as_deref());
+        assert!(tx.contract_code.value.is_empty());
+        assert_eq!(
+            b"\0asm\x01\0\0\0".to_vec(),
+            tx.contract_code.load(&context).unwrap().into_owned());
+    } else {
+        panic!("scDeploy step expected");
+    }
+}
+
+#[test]
+fn test_lazy_file_loading_ignores_other_values() {
+    let context = InterpreterContext::builder()
+        .lazy_file_loading(true)
+        .file_loader(InMemoryFileLoader::new().with_file("x", b"contents"))
+        .build();
+    let value = BytesValue::interpret_from(ValueSubTree::from("str:file:x"), &context).unwrap();
+    assert_eq!(None, value.lazy_file);
+    assert_eq!(b"file:x".to_vec(), value.load(&context).unwrap().into_owned());
+}
+
+#[test]
+fn test_io_error() {
+    match try_parse_scenario_raw("./tests/scenarios/missing.scen.json") {
+        Err(ParseScenarioError::Io { path, error }) => {
+            assert_eq!(Path::new("./tests/scenarios/missing.scen.json"), path);
+            assert_eq!(std::io::ErrorKind::NotFound, error.kind());
+        },
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_syntax_error() {
+    match try_parse_scenario_raw("./tests/scenarios/bad_syntax.scen.json") {
+        Err(ParseScenarioError::Syntax { line, column, message, .. }) => {
+            assert_eq!((7, 9), (line, column));
+            assert_eq!("expected `,` or `]`", message);
+        },
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_schema_error() {
+    let err = try_parse_scenario_raw("./tests/scenarios/unknown_step.scen.json").map(|_| ()).unwrap_err();
+    assert!(matches!(err, ParseScenarioError::Schema { line: 5, .. }), "unexpected error: {:?}", err);
+    assert!(err.to_string().contains("unknown variant `launchRocket`"), "unexpected message: {}", err);
+}
+
+#[test]
+fn test_interpret_error() {
+    let err = try_parse_scenario("./tests/scenarios/bad_value.scen.json").map(|_| ()).unwrap_err();
+    assert_eq!(
+        "./tests/scenarios/bad_value.scen.json:9:26: representation of 1000 does not fit in 1 bytes",
+        err.to_string());
+}
+
+#[test]
+fn test_parse_with_strict_context() {
+    let path = "tests/scenarios/unknown_fields.scen.json";
+    assert!(try_parse_scenario(path).is_ok());
+
+    let context = InterpreterContext::builder()
+        .scenario_file(path)
+        .unknown_field_policy(UnknownFieldPolicy::Reject)
+        .build();
+    assert!(matches!(
+        try_parse_scenario_with(path, &context),
+        Err(ParseScenarioError::UnknownField { .. })));
+}
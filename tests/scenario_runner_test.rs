@@ -0,0 +1,149 @@
+
+extern crate mandos;
+use mandos::*;
+
+use num_bigint::BigUint;
+
+/// Keeps a running sum in storage, under `sum`.
+#[derive(Default)]
+struct AdderMock {
+    calls: usize,
+}
+
+impl AdderMock {
+    fn add(world: &mut WorldState, contract: &[u8; 32], argument: &BytesValue) -> Result<(), ExecutionError> {
+        let account = world.account_mut(contract)
+            .ok_or(ExecutionError::UnknownAccount { address: *contract })?;
+        let sum = BigUint::from_bytes_be(account.storage_value(b"sum")) + BigUint::from_bytes_be(&argument.value);
+        account.storage.insert(b"sum".to_vec(), sum.to_bytes_be());
+        Ok(())
+    }
+
+    fn increment_nonce(world: &mut WorldState, sender: &[u8; 32]) -> Result<u64, ExecutionError> {
+        let account = world.account_mut(sender)
+            .ok_or(ExecutionError::UnknownAccount { address: *sender })?;
+        account.nonce += 1;
+        Ok(account.nonce - 1)
+    }
+}
+
+impl ContractExecutor for AdderMock {
+    fn sc_call(&mut self, tx: &TxCall, world: &mut WorldState) -> Result<TxResult, ExecutionError> {
+        self.calls += 1;
+        if tx.function != "add" {
+            return Err(ExecutionError::Custom(format!("no function {}", tx.function)));
+        }
+        AdderMock::increment_nonce(world, &tx.from.value)?;
+        AdderMock::add(world, &tx.to.value, &tx.arguments[0])?;
+        Ok(TxResult {
+            out: vec![world.account(&tx.to.value).unwrap().storage_value(b"sum").to_vec()],
+            gas: 100,
+            ..Default::default()
+        })
+    }
+
+    fn sc_deploy(&mut self, tx: &TxDeploy, world: &mut WorldState) -> Result<TxResult, ExecutionError> {
+        self.calls += 1;
+        let nonce = AdderMock::increment_nonce(world, &tx.from.value)?;
+        let contract = world.new_address(&tx.from.value, nonce)
+            .ok_or_else(|| ExecutionError::Custom("no new address".to_string()))?;
+        world.accounts.insert(contract, AccountState {
+            code: Some(tx.contract_code.value.clone()),
+            ..Default::default()
+        });
+        AdderMock::add(world, &contract, &tx.arguments[0])?;
+        Ok(TxResult::default())
+    }
+}
+
+#[test]
+fn test_run_steps_with_executor() {
+    let scenario = parse_scenario("tests/scenarios/world/adder.scen.json");
+    let mut runner = ScenarioRunner::new(AdderMock::default());
+
+    assert_eq!(None, runner.run_step(&scenario.steps[0]).unwrap());
+    assert_eq!(Some(TxResult::default()), runner.run_step(&scenario.steps[1]).unwrap());
+    let result = runner.run_step(&scenario.steps[2]).unwrap().unwrap();
+    assert_eq!(vec![vec![12u8]], result.out);
+    assert_eq!(100, result.gas);
+    assert_eq!(None, runner.run_step(&scenario.steps[3]).unwrap());
+    assert_eq!(2, runner.executor().calls);
+}
+
+#[test]
+fn test_run_scenario_error() {
+    let scenario = parse_scenario("tests/scenarios/world/adder.scen.json");
+    let mut runner = ScenarioRunner::new(AdderMock::default());
+
+    let err = runner.run_scenario(&scenario).unwrap_err();
+    assert_eq!(4, err.step_index);
+    assert_eq!(Some("bad".to_string()), err.tx_id);
+    assert_eq!(ExecutionError::Custom("no function subtract".to_string()), *err.error);
+    assert_eq!("step 4 (txId bad): no function subtract", err.to_string());
+}
+
+#[test]
+fn test_run_without_contracts() {
+    let scenario = parse_scenario("tests/scenarios/world/transfer.scen.json");
+    let mut runner = ScenarioRunner::new(NoContractExecutor);
+    runner.run_scenario(&scenario).unwrap();
+
+    let scenario = parse_scenario("tests/scenarios/world/adder.scen.json");
+    let mut runner = ScenarioRunner::new(NoContractExecutor);
+    let err = runner.run_scenario(&scenario).unwrap_err();
+    assert_eq!(ExecutionError::UnsupportedStep { step: "scDeploy" }, *err.error);
+}
+
+#[test]
+fn test_run_resolved_scenario() {
+    let scenario = resolve_scenario("tests/scenarios/external/main.scen.json");
+    let mut runner = ScenarioRunner::new(NoContractExecutor);
+    runner.run_resolved_scenario(&scenario).unwrap();
+    assert_eq!(WorldState::new(), runner.into_world());
+}
+
+#[test]
+fn test_run_step_checks_expect() {
+    let scenario = parse_scenario("tests/scenarios/world/adder.scen.json");
+    let mut runner = ScenarioRunner::new(AdderMock::default());
+    runner.run_step(&scenario.steps[0]).unwrap();
+    runner.run_step(&scenario.steps[1]).unwrap();
+
+    // adding a second time makes the sum 19 instead of the expected 12
+    runner.run_step(&scenario.steps[2]).unwrap();
+    let err = runner.run_step(&scenario.steps[2]).unwrap_err();
+    assert_eq!(
+        ExecutionError::TxMismatch {
+            mismatches: vec![TxMismatch::Out { expected: r#"["12"]"#.to_string(), actual: r#"["19"]"#.to_string() }],
+        },
+        err);
+}
+
+#[test]
+fn test_check_gas_override() {
+    let mut scenario = parse_scenario("tests/scenarios/world/adder.scen.json");
+    scenario.steps.truncate(4);
+    if let Step::ScCall { expect: Some(expect), .. } = &mut scenario.steps[2] {
+        expect.gas = Some(CheckValue::Equal(U64Value::from(99)));
+    }
+
+    let mut runner = ScenarioRunner::new(AdderMock::default());
+    assert_eq!(None, runner.check_gas());
+    let err = runner.run_scenario(&scenario).unwrap_err();
+    assert_eq!(
+        ExecutionError::TxMismatch {
+            mismatches: vec![TxMismatch::Gas { expected: "99".to_string(), actual: "100".to_string() }],
+        },
+        *err.error);
+
+    // the scenario's checkGas does not win over the runner's setting
+    scenario.check_gas = Some(true);
+    let mut runner = ScenarioRunner::new(AdderMock::default());
+    runner.set_check_gas(false);
+    runner.run_scenario(&scenario).unwrap();
+    assert_eq!(Some(false), runner.check_gas());
+
+    scenario.check_gas = Some(false);
+    let mut runner = ScenarioRunner::new(AdderMock::default());
+    runner.run_scenario(&scenario).unwrap();
+}
@@ -3,10 +3,6 @@ extern crate mandos;
 use mandos::*;
 
 use std::fs;
-use std::fs::File;
-use std::io::Write;
-
-use serde::Serialize;
 
 #[test]
 fn test_scenario_raw_ser_de() {
@@ -15,16 +11,10 @@ fn test_scenario_raw_ser_de() {
 
     let scen: ScenarioRaw = serde_json::from_str(contents.as_str()).unwrap();
 
-    // let serialized = serde_json::to_string_pretty(&scen).unwrap();
-    let buf = Vec::new();
-    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
-    let mut ser = serde_json::Serializer::with_formatter(buf, formatter);
-    scen.serialize(&mut ser).unwrap();
-    let mut serialized = String::from_utf8(ser.into_inner()).unwrap();
-    serialized.push('\n');
+    let serialized = scenario_to_string(&scen);
 
-    let mut file = File::create("serialized.scen.json").unwrap();
-    file.write_all(serialized.as_bytes()).unwrap();
+    write_scenario("serialized.scen.json", &scen).unwrap();
+    assert_eq!(serialized, fs::read_to_string("serialized.scen.json").unwrap());
     assert_eq!(serialized, contents);
 }
 
@@ -37,11 +27,150 @@ fn test_ser() {
         check_gas: Some(false),
         steps: vec![
             StepRaw::ExternalSteps{
-                path: String::from("hello.txt")
+                path: String::from("hello.txt"),
+                unknown_fields: UnknownFields::new(),
             }
-        ]
+        ],
+        unknown_fields: UnknownFields::new(),
     };
 
     let serialized = serde_json::to_string_pretty(&scen).unwrap();
     println!("serialized = {}", serialized);
 }
+
+#[test]
+fn test_scenario_to_string_layout() {
+    let scen = ScenarioRaw{
+        name: Some("layout".to_string()),
+        comment: None,
+        check_gas: None,
+        steps: vec![
+            StepRaw::DumpState{
+                comment: None,
+                unknown_fields: UnknownFields::new(),
+            }
+        ],
+        unknown_fields: UnknownFields::new(),
+    };
+
+    let expected = r#"{
+    "name": "layout",
+    "steps": [
+        {
+            "step": "dumpState"
+        }
+    ]
+}
+"#;
+    assert_eq!(expected, scenario_to_string(&scen));
+}
+
+#[test]
+fn test_field_order_and_unknown_fields_round_trip() {
+    let path = "tests/scenarios/unknown_fields.scen.json";
+    let contents = fs::read_to_string(path).unwrap();
+
+    let scen = parse_scenario_raw(path);
+    assert_eq!(contents, scenario_to_string(&scen));
+    assert_eq!(
+        vec![
+            "generator".to_string(),
+            "steps[0].accounts[\"address:owner\"].owner".to_string(),
+            "steps[1].ticket".to_string(),
+            "steps[1].tx.gasLimt".to_string(),
+        ],
+        scen.unknown_field_paths());
+}
+
+#[test]
+fn test_unknown_fields_written_back_in_place() {
+    let path = "tests/scenarios/unknown_fields_in_between.scen.json";
+    let contents = fs::read_to_string(path).unwrap();
+
+    let scen = parse_scenario_raw(path);
+    assert_eq!(contents, scenario_to_string(&scen));
+    assert_eq!(contents, scenario_to_string(&parse_scenario(path).into_raw()));
+}
+
+#[test]
+fn test_unknown_fields_without_position_written_last() {
+    let contents = fs::read_to_string("tests/scenarios/unknown_fields_in_between.scen.json").unwrap();
+    let scen: ScenarioRaw = serde_json::from_str(&contents).unwrap();
+    let written = scenario_to_string(&scen);
+    assert!(written.starts_with("{\n    \"name\": \"unknown fields in between known ones\",\n    \"steps\": ["), "{}", written);
+    assert!(written.ends_with("    ],\n    \"generator\": \"in-house\"\n}\n"), "{}", written);
+}
+
+#[test]
+fn test_unknown_fields_kept_by_interpreted_scenario() {
+    let path = "tests/scenarios/unknown_fields.scen.json";
+    let contents = fs::read_to_string(path).unwrap();
+
+    let scen = parse_scenario(path);
+    assert_eq!(contents, scenario_to_string(&scen.into_raw()));
+}
+
+#[test]
+fn test_map_value_interpreted_in_file_order() {
+    let scen = parse_scenario_raw("tests/scenarios/unknown_fields.scen.json");
+    let context = &InterpreterContext::default();
+    if let StepRaw::ScCall { tx, .. } = &scen.steps[1] {
+        assert_eq!(
+            vec![0, 0, 0, 2, 1],
+            interpret_subtree(&tx.arguments[0], context).unwrap());
+    } else {
+        panic!("scCall step expected");
+    }
+}
+
+#[test]
+fn test_reject_unknown_fields() {
+    let path = "tests/scenarios/unknown_fields.scen.json";
+    let err = try_parse_scenario_raw_with_policy(path, UnknownFieldPolicy::Reject).map(|_| ()).unwrap_err();
+    match err {
+        ParseScenarioError::UnknownField { line, column, field, suggestion, .. } => {
+            assert_eq!((14, 21), (line, column));
+            assert_eq!("steps[0].accounts[\"address:owner\"].owner", field);
+            assert_eq!(None, suggestion);
+        },
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    // the first unknown key in the file is reported, wherever its object is
+    let path = "tests/scenarios/unknown_fields_in_between.scen.json";
+    let err = try_parse_scenario_raw_with_policy(path, UnknownFieldPolicy::Reject).map(|_| ()).unwrap_err();
+    assert_eq!("tests/scenarios/unknown_fields_in_between.scen.json:3:5: unknown field generator", err.to_string());
+
+    assert!(try_parse_scenario_raw_with_policy("./example.scen.json", UnknownFieldPolicy::Reject).is_ok());
+}
+
+#[test]
+fn test_strict_mode_suggests_field_name() {
+    let path = "tests/scenarios/misspelled_field.scen.json";
+
+    // without strict mode, serde only knows that the correct spelling is missing
+    let err = try_parse_scenario_raw(path).map(|_| ()).unwrap_err();
+    assert!(matches!(err, ParseScenarioError::Schema { .. }));
+
+    let err = try_parse_scenario_raw_with_policy(path, UnknownFieldPolicy::Reject).map(|_| ()).unwrap_err();
+    assert_eq!(
+        "tests/scenarios/misspelled_field.scen.json:13:17: unknown field steps[0].tx.gasLimt, did you mean `gasLimit`?",
+        err.to_string());
+
+    let path = "tests/scenarios/misspelled_account_field.scen.json";
+    assert!(try_parse_scenario_raw(path).is_ok());
+    let err = try_parse_scenario_raw_with_policy(path, UnknownFieldPolicy::Reject).map(|_| ()).unwrap_err();
+    match err {
+        ParseScenarioError::UnknownField { field, suggestion, .. } => {
+            assert_eq!("steps[0].accounts[\"address:owner\"].asyncCalldata", field);
+            assert_eq!(Some("asyncCallData".to_string()), suggestion);
+        },
+        other => panic!("unexpected error: {:?}", other),
+    }
+
+    let path = "tests/scenarios/misspelled_account_nonce.scen.json";
+    let err = try_parse_scenario_raw_with_policy(path, UnknownFieldPolicy::Reject).map(|_| ()).unwrap_err();
+    assert_eq!(
+        "tests/scenarios/misspelled_account_nonce.scen.json:8:21: unknown field steps[0].accounts[\"address:owner\"].nonse, did you mean `nonce`?",
+        err.to_string());
+}
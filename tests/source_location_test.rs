@@ -0,0 +1,126 @@
+
+extern crate mandos;
+use mandos::*;
+
+use std::path::Path;
+
+const SCENARIO: &str = r#"{
+    "steps": [
+        {
+            "step": "transfer",
+            "txId": "1",
+            "tx": {
+                "from": "address:a",
+                "to": "address:b",
+                "value": "0x12g4"
+            }
+        }
+    ]
+}"#;
+
+#[test]
+fn test_value_locations() {
+    let mut raw: ScenarioRaw = serde_json::from_str(SCENARIO).unwrap();
+    locate_values(&mut raw, SCENARIO, None);
+    if let StepRaw::Transfer { tx, .. } = &raw.steps[0] {
+        assert_eq!(
+            Some(&SourceLocation { file: None, line: 7, column: 25 }),
+            tx.from.location());
+        assert_eq!(
+            Some(&SourceLocation { file: None, line: 9, column: 26 }),
+            tx.value.location());
+    } else {
+        panic!("transfer step expected");
+    }
+}
+
+#[test]
+fn test_plain_string_fields_are_not_values() {
+    let contents = r#"{
+    "steps": [
+        {
+            "step": "transfer",
+            "txId": "1",
+            "comment": "transfer",
+            "tx": {
+                "value": "1",
+                "from": "address:a",
+                "to": "address:b"
+            }
+        }
+    ]
+}"#;
+    let mut raw: ScenarioRaw = serde_json::from_str(contents).unwrap();
+    locate_values(&mut raw, contents, None);
+    if let StepRaw::Transfer { tx, .. } = &raw.steps[0] {
+        assert_eq!(
+            Some(&SourceLocation { file: None, line: 8, column: 26 }),
+            tx.value.location());
+    } else {
+        panic!("transfer step expected");
+    }
+}
+
+#[test]
+fn test_unknown_field_with_the_same_text() {
+    let contents = r#"{
+    "steps": [
+        {
+            "step": "transfer",
+            "txId": "1",
+            "tx": {
+                "from": "address:a",
+                "note": "address:b",
+                "to": "address:b",
+                "value": "1"
+            }
+        }
+    ]
+}"#;
+    let mut raw: ScenarioRaw = serde_json::from_str(contents).unwrap();
+    locate_values(&mut raw, contents, None);
+    if let StepRaw::Transfer { tx, .. } = &raw.steps[0] {
+        assert_eq!(
+            Some(&SourceLocation { file: None, line: 9, column: 23 }),
+            tx.to.location());
+    } else {
+        panic!("transfer step expected");
+    }
+}
+
+#[test]
+fn test_no_locations_without_locating() {
+    let raw: ScenarioRaw = serde_json::from_str(SCENARIO).unwrap();
+    if let StepRaw::Transfer { tx, .. } = &raw.steps[0] {
+        assert_eq!(None, tx.from.location());
+    } else {
+        panic!("transfer step expected");
+    }
+}
+
+#[test]
+fn test_interpret_error_location() {
+    let file = Path::new("transfer.scen.json");
+    let mut raw: ScenarioRaw = serde_json::from_str(SCENARIO).unwrap();
+    locate_values(&mut raw, SCENARIO, Some(file));
+    let err = Scenario::interpret_from(raw, &InterpreterContext::default()).unwrap_err();
+    assert_eq!(
+        MandosError::Located {
+            location: SourceLocation { file: Some(Path::new("transfer.scen.json").into()), line: 9, column: 26 },
+            error: Box::new(MandosError::InvalidHex("0x12g4".to_string())),
+        },
+        err);
+    assert_eq!("transfer.scen.json:9:26: could not parse hex number: 0x12g4", err.to_string());
+}
+
+#[test]
+fn test_example_locations() {
+    let raw = parse_scenario_raw("./example.scen.json");
+    if let StepRaw::SetState { new_addresses, .. } = &raw.steps[1] {
+        let location = new_addresses[0].creator_nonce.location().unwrap();
+        assert_eq!(Some(Path::new("./example.scen.json").into()), location.file);
+        assert_eq!((46, 37), (location.line, location.column));
+    } else {
+        panic!("setState step expected");
+    }
+}
@@ -0,0 +1,137 @@
+
+extern crate mandos;
+use mandos::*;
+
+#[test]
+fn test_matching_result() {
+    let context = &InterpreterContext::default();
+    let result = TxResult {
+        out: vec![vec![5], b"ok".to_vec()],
+        logs: vec![TxLog {
+            address: AddressValue::interpret_from(ValueSubTree::from("sc:adder"), context).unwrap().value,
+            identifier: b"added".to_vec(),
+            topics: vec![vec![5]],
+            data: Vec::new(),
+        }],
+        gas: 1000,
+        ..Default::default()
+    };
+    let raw: TxExpectRaw = serde_json::from_str(r#"{
+        "out": ["5", "str:ok"],
+        "status": "0",
+        "message": "",
+        "logs": [
+            {
+                "address": "sc:adder",
+                "identifier": "str:added",
+                "topics": ["5"],
+                "data": ""
+            }
+        ],
+        "gas": "1000",
+        "refund": "*"
+    }"#).unwrap();
+    let expect = TxExpect::interpret_from(raw, context).unwrap();
+    assert_eq!(Vec::<TxMismatch>::new(), expect.mismatches(&result, true));
+    assert_eq!(Ok(()), expect.check(&result, true));
+}
+
+#[test]
+fn test_each_field_reported() {
+    let context = &InterpreterContext::default();
+    let result = TxResult {
+        out: vec![vec![5], b"ok".to_vec()],
+        logs: vec![TxLog {
+            address: AddressValue::interpret_from(ValueSubTree::from("sc:adder"), context).unwrap().value,
+            identifier: b"added".to_vec(),
+            topics: vec![vec![5]],
+            data: Vec::new(),
+        }],
+        gas: 1000,
+        ..Default::default()
+    };
+    let raw: TxExpectRaw = serde_json::from_str(r#"{
+        "out": ["6", "*"],
+        "status": "4",
+        "message": "str:error",
+        "logs": [],
+        "gas": "999",
+        "refund": "1"
+    }"#).unwrap();
+    let expect = TxExpect::interpret_from(raw, context).unwrap();
+    assert_eq!(
+        vec![
+            TxMismatch::Out { expected: r#"["6","*"]"#.to_string(), actual: r#"["5","str:ok"]"#.to_string() },
+            TxMismatch::Status { expected: "4".to_string(), actual: "0".to_string() },
+            TxMismatch::Message { expected: "str:error".to_string(), actual: "".to_string() },
+            TxMismatch::Logs {
+                expected: "[]".to_string(),
+                actual: r#"[{"address":"sc:adder","identifier":"str:added","topics":["5"],"data":""}]"#.to_string(),
+            },
+            TxMismatch::Gas { expected: "999".to_string(), actual: "1000".to_string() },
+            TxMismatch::Refund { expected: "1".to_string(), actual: "0".to_string() },
+        ],
+        expect.mismatches(&result, true));
+
+    let message = expect.check(&result, true).unwrap_err().to_string();
+    assert!(message.starts_with("transaction result check failed with 6 mismatch(es)\n"), "{}", message);
+    assert!(message.contains("\n  - bad out: expected [\"6\",\"*\"], actual [\"5\",\"str:ok\"]"), "{}", message);
+    assert!(message.contains("\n  - bad status: expected \"4\", actual \"0\""), "{}", message);
+    assert!(message.contains("\n  - bad message: expected \"str:error\", actual \"\""), "{}", message);
+}
+
+#[test]
+fn test_gas_only_checked_if_requested() {
+    let result = TxResult {
+        out: vec![vec![5]],
+        gas: 1000,
+        ..Default::default()
+    };
+    let raw: TxExpectRaw = serde_json::from_str(r#"{
+        "out": ["5"],
+        "status": "0",
+        "gas": "999"
+    }"#).unwrap();
+    let expect = TxExpect::interpret_from(raw, &InterpreterContext::default()).unwrap();
+    assert_eq!(1, expect.mismatches(&result, true).len());
+    assert_eq!(Vec::<TxMismatch>::new(), expect.mismatches(&result, false));
+}
+
+#[test]
+fn test_log_stars() {
+    let context = &InterpreterContext::default();
+    let result = TxResult {
+        logs: vec![TxLog {
+            address: AddressValue::interpret_from(ValueSubTree::from("sc:adder"), context).unwrap().value,
+            identifier: b"added".to_vec(),
+            topics: vec![vec![5]],
+            data: Vec::new(),
+        }],
+        ..Default::default()
+    };
+
+    let raw: TxExpectRaw = serde_json::from_str(r#"{ "out": [], "status": "0" }"#).unwrap();
+    let default_star = TxExpect::interpret_from(raw, context).unwrap();
+    assert!(matches!(default_star.logs, CheckLogs::DefaultStar));
+    assert_eq!(Vec::<TxMismatch>::new(), default_star.mismatches(&result, true));
+
+    let raw: TxExpectRaw = serde_json::from_str(r#"{ "out": [], "status": "0", "logs": "*" }"#).unwrap();
+    let star = TxExpect::interpret_from(raw, context).unwrap();
+    assert!(star.logs.is_star());
+    assert_eq!(Vec::<TxMismatch>::new(), star.mismatches(&result, true));
+
+    let raw: TxExpectRaw = serde_json::from_str(r#"{
+        "out": [],
+        "status": "0",
+        "logs": [
+            {
+                "address": "sc:adder",
+                "identifier": "str:added",
+                "topics": ["6"],
+                "data": ""
+            }
+        ]
+    }"#).unwrap();
+    let wrong_topic = TxExpect::interpret_from(raw, context).unwrap();
+    assert!(matches!(wrong_topic.mismatches(&result, true)[..], [TxMismatch::Logs { .. }]));
+}
@@ -0,0 +1,68 @@
+
+extern crate mandos;
+use mandos::*;
+
+use num_bigint::BigUint;
+
+fn reinterpret(original: &ValueSubTree) -> Vec<u8> {
+    interpret_subtree(original, &InterpreterContext::default()).unwrap()
+}
+
+#[test]
+fn test_bytes_value_from_native() {
+    let value = BytesValue::from("abc");
+    assert_eq!("\"str:abc\"", value.to_string());
+    assert_eq!(b"abc".to_vec(), value.value);
+    assert_eq!(ValueSubTree::from("str:abc"), value.original);
+
+    let value = BytesValue::from(vec![0x12, 0x34]);
+    assert_eq!(ValueSubTree::from("4660"), value.original);
+    assert_eq!(value.value, reinterpret(&value.original));
+
+    let value = BytesValue::from(vec![0x00, 0x01]);
+    assert_eq!(value.value, reinterpret(&value.original));
+
+    assert_eq!(ValueSubTree::from(""), BytesValue::from(Vec::new()).original);
+}
+
+#[test]
+fn test_bytes_key_from_native() {
+    let key = BytesKey::from("key");
+    assert_eq!("str:key", key.original);
+    assert_eq!(BytesKey::from(b"key".to_vec()), key);
+
+    let key = BytesKey::from(vec![0xff, 0x00]);
+    assert_eq!(key.value, reinterpret(&ValueSubTree::from(key.original.as_str())));
+}
+
+#[test]
+fn test_number_values_from_native() {
+    let value = BigUintValue::from(BigUint::from(1u32) << 70);
+    assert_eq!(ValueSubTree::from("1180591620717411303424"), value.original);
+
+    let value = BigUintValue::from(5u64);
+    assert_eq!(BigUint::from(5u32), value.value);
+    assert_eq!(ValueSubTree::from("5"), value.original);
+
+    let value = U64Value::from(u64::MAX);
+    assert_eq!(u64::MAX, value.value);
+    assert_eq!(ValueSubTree::from("18446744073709551615"), value.original);
+}
+
+#[test]
+fn test_address_from_native() {
+    let context = &InterpreterContext::default();
+    let bytes = interpret_string("address:owner", context).unwrap();
+    let mut array = [0u8; 32];
+    array.copy_from_slice(&bytes);
+
+    let value = AddressValue::from(array);
+    assert_eq!(ValueSubTree::from("address:owner"), value.original);
+
+    let key = AddressKey::from(array);
+    assert_eq!("address:owner", key.original);
+    assert_eq!(value, AddressValue::from(&key));
+
+    let value = AddressValue::from([0xab; 32]);
+    assert_eq!(value.value.to_vec(), reinterpret(&value.original));
+}
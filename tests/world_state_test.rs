@@ -0,0 +1,218 @@
+
+extern crate mandos;
+use mandos::*;
+
+use num_bigint::BigUint;
+
+fn address(s: &str) -> [u8; 32] {
+    let mut address = [0u8; 32];
+    address.copy_from_slice(&interpret_string(s, &InterpreterContext::default()).unwrap());
+    address
+}
+
+#[test]
+fn test_apply_set_state() {
+    let scenario = parse_scenario("tests/scenarios/world/set_state.scen.json");
+    let mut world = WorldState::new();
+
+    assert!(world.apply_set_state(&scenario.steps[0]).unwrap());
+    let alice = world.account(&address("address:alice")).unwrap();
+    assert_eq!(1, alice.nonce);
+    assert_eq!(BigUint::from(1000u32), alice.balance);
+    assert_eq!(b"\x01".as_ref(), alice.storage_value(b"kept"));
+    assert!(!alice.storage.contains_key(b"cleared".as_ref()));
+    assert_eq!(b"".as_ref(), alice.storage_value(b"cleared"));
+    assert_eq!(None, alice.code);
+    assert_eq!(vec![vec![1u8]], world.block_hashes);
+    assert_eq!(Some(BlockInfoState { nonce: 10, ..Default::default() }), world.previous_block_info);
+    assert_eq!(Some(BlockInfoState { timestamp: 500, nonce: 11, ..Default::default() }), world.current_block_info);
+    assert_eq!(Some(address("sc:first")), world.new_address(&address("address:alice"), 1));
+
+    assert!(world.apply_set_state(&scenario.steps[1]).unwrap());
+    let alice = world.account(&address("address:alice")).unwrap();
+    assert_eq!(5, alice.nonce);
+    assert_eq!(BigUint::from(7u32), alice.balance);
+    assert!(alice.storage.is_empty(), "accounts are replaced as a whole");
+    assert_eq!(Some(b"contract code".to_vec()), alice.code);
+    assert!(world.account(&address("address:bob")).is_some(), "unlisted accounts are kept");
+
+    assert_eq!(2, world.new_addresses.len());
+    assert_eq!(Some(address("sc:first")), world.new_address(&address("address:alice"), 1));
+    assert_eq!(Some(address("sc:second")), world.new_address(&address("address:alice"), 5));
+    assert_eq!(None, world.new_address(&address("address:bob"), 1));
+
+    assert!(world.block_hashes.is_empty());
+    assert_eq!(None, world.previous_block_info);
+    assert_eq!(Some(BlockInfoState { round: 3, ..Default::default() }), world.current_block_info);
+}
+
+#[test]
+fn test_apply_set_state_ignores_other_steps() {
+    let mut world = WorldState::new();
+    assert!(!world.apply_set_state(&Step::DumpState { comment: None, unknown_fields: UnknownFields::new() }).unwrap());
+    assert_eq!(WorldState::new(), world);
+}
+
+fn world_after_transfers() -> WorldState {
+    let scenario = parse_scenario("tests/scenarios/world/transfer.scen.json");
+    let mut world = WorldState::new();
+    for step in scenario.steps.iter() {
+        world.execute_step(step).unwrap();
+    }
+    world
+}
+
+#[test]
+fn test_transfer_and_validator_reward() {
+    let world = world_after_transfers();
+
+    let alice = world.account(&address("address:alice")).unwrap();
+    assert_eq!(BigUint::from(600u32), alice.balance);
+    assert_eq!(4, alice.nonce);
+
+    let bob = world.account(&address("address:bob")).unwrap();
+    assert_eq!(BigUint::from(450u32), bob.balance);
+    assert_eq!(0, bob.nonce, "neither receiving nor rewards change the nonce");
+}
+
+#[test]
+fn test_transfer_errors() {
+    let mut world = world_after_transfers();
+    let before = world.clone();
+
+    let err = world.transfer(&TxTransfer {
+        from: AddressValue::from(address("address:alice")),
+        to: AddressValue::from(address("address:bob")),
+        value: BigUintValue::from(601u64),
+        unknown_fields: UnknownFields::new(),
+    }).unwrap_err();
+    assert_eq!(
+        ExecutionError::InsufficientFunds {
+            address: address("address:alice"),
+            balance: BigUint::from(600u32),
+            value: BigUint::from(601u32),
+        },
+        err);
+    assert_eq!("insufficient funds: account address:alice has 600, needs 601", err.to_string());
+
+    let err = world.transfer(&TxTransfer {
+        from: AddressValue::from(address("address:carol")),
+        to: AddressValue::from(address("address:bob")),
+        value: BigUintValue::from(1u64),
+        unknown_fields: UnknownFields::new(),
+    }).unwrap_err();
+    assert_eq!(ExecutionError::UnknownAccount { address: address("address:carol") }, err);
+    assert_eq!("unknown account address:carol", err.to_string());
+
+    let err = world.transfer(&TxTransfer {
+        from: AddressValue::from(address("address:alice")),
+        to: AddressValue::from(address("address:carol")),
+        value: BigUintValue::from(1u64),
+        unknown_fields: UnknownFields::new(),
+    }).unwrap_err();
+    assert_eq!(ExecutionError::UnknownAccount { address: address("address:carol") }, err);
+
+    let err = world.validator_reward(&TxValidatorReward {
+        to: AddressValue::from(address("address:carol")),
+        value: BigUintValue::from(1u64),
+        unknown_fields: UnknownFields::new(),
+    }).unwrap_err();
+    assert_eq!(ExecutionError::UnknownAccount { address: address("address:carol") }, err);
+
+    assert_eq!(before, world, "failed transactions leave the state unchanged");
+}
+
+#[test]
+fn test_unsupported_step() {
+    let mut world = WorldState::new();
+    let step = Step::ExternalSteps { path: "other.scen.json".to_string(), unknown_fields: UnknownFields::new() };
+    assert_eq!(Err(ExecutionError::UnsupportedStep { step: "externalSteps" }), world.execute_step(&step));
+}
+
+#[test]
+fn test_check_state_mismatches() {
+    let scenario = parse_scenario("tests/scenarios/world/check_state.scen.json");
+    let mut world = WorldState::new();
+    world.execute_step(&scenario.steps[0]).unwrap();
+
+    let accounts = match &scenario.steps[1] {
+        Step::CheckState { accounts, .. } => accounts,
+        other => panic!("checkState step expected, got {:?}", other),
+    };
+    let alice = "address:alice".to_string();
+    assert_eq!(
+        vec![
+            StateMismatch::Nonce { address: alice.clone(), expected: "2".to_string(), actual: "1".to_string() },
+            StateMismatch::Code { address: alice.clone(), expected: "str:new code".to_string(), actual: "str:old code".to_string() },
+            StateMismatch::AsyncCallData { address: alice.clone(), expected: "str:callback".to_string(), actual: "".to_string() },
+            StateMismatch::StorageValue {
+                address: alice.clone(),
+                key: "str:changed".to_string(),
+                expected: "6".to_string(),
+                actual: "5".to_string(),
+            },
+            StateMismatch::MissingStorageKey { address: alice.clone(), key: "str:missing".to_string(), expected: "1".to_string() },
+            StateMismatch::StorageValue {
+                address: alice.clone(),
+                key: "str:present".to_string(),
+                expected: "".to_string(),
+                actual: "7".to_string(),
+            },
+            StateMismatch::UnexpectedStorageKey { address: alice, key: "str:extra".to_string(), actual: "1".to_string() },
+            StateMismatch::MissingAccount { address: "address:carol".to_string() },
+            StateMismatch::UnexpectedAccount { address: "address:bob".to_string() },
+        ],
+        accounts.mismatches(&world).unwrap());
+
+    let err = world.execute_step(&scenario.steps[1]).unwrap_err();
+    assert!(matches!(&err, ExecutionError::StateMismatch { mismatches } if mismatches.len() == 9));
+    let message = err.to_string();
+    assert!(message.starts_with("check state failed with 9 mismatch(es)\n"), "{}", message);
+    assert!(message.contains("\n  - bad nonce for account address:alice: expected \"2\", actual \"1\""), "{}", message);
+    assert!(message.contains("\n  - bad storage value for key str:present of account address:alice: expected \"\", actual \"7\""), "{}", message);
+}
+
+#[test]
+fn test_set_state_with_lazily_loaded_code() {
+    let path = "tests/scenarios/world/file_code.scen.json";
+    let context = InterpreterContext::builder()
+        .scenario_file(path)
+        .lazy_file_loading(true)
+        .build();
+    let scenario = try_parse_scenario_with(path, &context).unwrap();
+
+    let mut world = WorldState::new();
+    let err = world.apply_set_state(&scenario.steps[0]).unwrap_err();
+    assert_eq!(
+        "tests/scenarios/world/file_code.scen.json:11:29: file tests/scenarios/world/../fake-contract.wasm was not loaded",
+        err.to_string());
+    assert_eq!(WorldState::new(), world);
+    assert_eq!(Err(ExecutionError::Interpret(err)), world.execute_step(&scenario.steps[0]));
+
+    if let Step::SetState { accounts, .. } = &scenario.steps[0] {
+        let (_, account) = accounts.iter().next().unwrap();
+        let state = AccountState::load(account, &context).unwrap();
+        assert_eq!(Some(b"\0asm\x01\0\0\0".to_vec()), state.code);
+    } else {
+        panic!("setState step expected");
+    }
+}
+
+#[test]
+fn test_check_state_with_lazily_loaded_code() {
+    let path = "tests/scenarios/world/file_code.scen.json";
+    let mut world = WorldState::new();
+    for step in parse_scenario(path).steps.iter() {
+        world.execute_step(step).unwrap();
+    }
+
+    let context = InterpreterContext::builder()
+        .scenario_file(path)
+        .lazy_file_loading(true)
+        .build();
+    let scenario = try_parse_scenario_with(path, &context).unwrap();
+    let err = world.execute_step(&scenario.steps[1]).unwrap_err();
+    assert_eq!(
+        "tests/scenarios/world/file_code.scen.json:22:29: file tests/scenarios/world/../fake-contract.wasm was not loaded",
+        err.to_string());
+}